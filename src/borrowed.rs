@@ -0,0 +1,135 @@
+use std::borrow::Cow;
+
+use crate::{parse_document, Build, JSONParseResult, JSONValue, ParseOptions, Span};
+
+/// The same as [`JSONValue`], except strings and object keys borrow from
+/// the input they were parsed from, so reading a document doesn't copy
+/// them. Only a string with escapes in it is decoded into an owned copy
+#[derive(Debug, Clone, PartialEq)]
+pub enum JSONValueRef<'a> {
+    String(Cow<'a, str>),
+    Number(f64),
+    Integer(i64),
+    RawNumber(String),
+    Object(Vec<(Cow<'a, str>, JSONValueRef<'a>)>),
+    Array(Vec<JSONValueRef<'a>>),
+    True,
+    False,
+    Null,
+}
+
+/// Parses a single JSON document like `parse_json`, borrowing strings from
+/// `string` wherever it can
+pub fn parse_json_borrowed(string: &str) -> JSONParseResult<JSONValueRef<'_>> {
+    return parse_document::<BuildBorrowed>(string, &ParseOptions::default());
+}
+
+impl JSONValueRef<'_> {
+    /// Copies out everything that's borrowed, leaving a plain value
+    pub fn into_value(self) -> JSONValue {
+        return match self {
+            JSONValueRef::String(string) => JSONValue::String(string.into_owned().into()),
+            JSONValueRef::Number(number) => JSONValue::Number(number),
+            JSONValueRef::Integer(integer) => JSONValue::Integer(integer),
+            JSONValueRef::RawNumber(text) => JSONValue::RawNumber(text),
+            JSONValueRef::Object(members) => JSONValue::Object(
+                members
+                    .into_iter()
+                    .map(|(key, value)| (key.into_owned().into(), value.into_value()))
+                    .collect(),
+            ),
+            JSONValueRef::Array(items) => {
+                JSONValue::Array(items.into_iter().map(JSONValueRef::into_value).collect())
+            }
+            JSONValueRef::True => JSONValue::True,
+            JSONValueRef::False => JSONValue::False,
+            JSONValueRef::Null => JSONValue::Null,
+        };
+    }
+}
+
+struct BuildBorrowed;
+
+impl<'a> Build<'a> for BuildBorrowed {
+    type Key = Cow<'a, str>;
+    type Value = JSONValueRef<'a>;
+
+    fn key(key: Cow<'a, str>, _: Span) -> Cow<'a, str> {
+        return key;
+    }
+
+    fn scalar(value: JSONValue, _: Span) -> JSONValueRef<'a> {
+        return match value {
+            JSONValue::String(string) => JSONValueRef::String(Cow::Owned(string.into_string())),
+            JSONValue::Number(number) => JSONValueRef::Number(number),
+            JSONValue::Integer(integer) => JSONValueRef::Integer(integer),
+            JSONValue::RawNumber(text) => JSONValueRef::RawNumber(text),
+            JSONValue::True => JSONValueRef::True,
+            JSONValue::False => JSONValueRef::False,
+            JSONValue::Null => JSONValueRef::Null,
+            JSONValue::Object(_) | JSONValue::Array(_) => {
+                unreachable!("arrays and objects aren't scalars")
+            }
+        };
+    }
+
+    fn string(string: Cow<'a, str>, _: Span) -> JSONValueRef<'a> {
+        return JSONValueRef::String(string);
+    }
+
+    fn array(items: Vec<JSONValueRef<'a>>, _: Span) -> JSONValueRef<'a> {
+        return JSONValueRef::Array(items);
+    }
+
+    fn object(members: Vec<(Cow<'a, str>, JSONValueRef<'a>)>, _: Span) -> JSONValueRef<'a> {
+        return JSONValueRef::Object(members);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+
+    /// Whether `string` points into `input`, rather than at a copy
+    fn borrows_from(string: &str, input: &str) -> bool {
+        return input.as_bytes().as_ptr_range().contains(&string.as_ptr());
+    }
+
+    #[test]
+    fn parse_json_borrowed_borrows_strings_without_escapes() {
+        let input = r#"{"plain": "no escapes", "escaped\n": "tab\there"}"#;
+        let JSONValueRef::Object(members) = parse_json_borrowed(input).unwrap() else {
+            panic!("expected an object");
+        };
+
+        let (key, value) = &members[0];
+        assert!(borrows_from(key, input));
+        let JSONValueRef::String(string) = value else {
+            panic!("expected a string");
+        };
+        assert_eq!(string, "no escapes");
+        assert!(borrows_from(string, input));
+
+        let (key, value) = &members[1];
+        assert_eq!(key, "escaped\n");
+        assert!(matches!(key, Cow::Owned(_)));
+        assert_eq!(
+            value,
+            &JSONValueRef::String(Cow::Owned("tab\there".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_json_borrowed_matches_parse_json() {
+        for input in [fixtures::KITCHEN_SINK, fixtures::NESTED_OBJECT, r#""é""#] {
+            assert_eq!(
+                parse_json_borrowed(input).map(JSONValueRef::into_value),
+                parse_json(input)
+            );
+        }
+        for input in ["", "[1,]", r#"{"a" 1}"#, r#""\x""#] {
+            assert_eq!(parse_json_borrowed(input).err(), parse_json(input).err());
+        }
+    }
+}
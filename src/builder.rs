@@ -0,0 +1,139 @@
+use crate::{JSONString, JSONValue};
+
+/// Entry point for building a [`JSONValue`] by hand, with a fluent builder
+/// for each kind of container
+pub struct JSONBuilder;
+
+impl JSONBuilder {
+    /// Starts building an empty object
+    pub fn object() -> JSONObjectBuilder {
+        return JSONObjectBuilder { members: vec![] };
+    }
+
+    /// Starts building an empty array
+    pub fn array() -> JSONArrayBuilder {
+        return JSONArrayBuilder { items: vec![] };
+    }
+}
+
+/// Builds a `JSONValue::Object`, keeping members in the order they're added
+#[derive(Debug, Clone, PartialEq)]
+pub struct JSONObjectBuilder {
+    members: Vec<(JSONString, JSONValue)>,
+}
+
+impl JSONObjectBuilder {
+    /// Adds a member. Keys aren't deduplicated, the same as when parsing
+    pub fn key(mut self, name: impl Into<JSONString>, value: impl Into<JSONValue>) -> Self {
+        self.members.push((name.into(), value.into()));
+        return self;
+    }
+
+    pub fn build(self) -> JSONValue {
+        return JSONValue::Object(self.members);
+    }
+}
+
+/// Builds a `JSONValue::Array`
+#[derive(Debug, Clone, PartialEq)]
+pub struct JSONArrayBuilder {
+    items: Vec<JSONValue>,
+}
+
+impl JSONArrayBuilder {
+    pub fn push(mut self, value: impl Into<JSONValue>) -> Self {
+        self.items.push(value.into());
+        return self;
+    }
+
+    pub fn build(self) -> JSONValue {
+        return JSONValue::Array(self.items);
+    }
+}
+
+/// Builds an object from key-value pairs in one go, keeping their order,
+/// like `object([("id", 1i64), ("count", 2i64)])`
+pub fn object<K, V>(pairs: impl IntoIterator<Item = (K, V)>) -> JSONValue
+where
+    K: Into<JSONString>,
+    V: Into<JSONValue>,
+{
+    return JSONValue::Object(
+        pairs
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect(),
+    );
+}
+
+/// Builds an array from items in one go, like `array([1i64, 2, 3])`
+pub fn array(items: impl IntoIterator<Item = impl Into<JSONValue>>) -> JSONValue {
+    return JSONValue::Array(items.into_iter().map(Into::into).collect());
+}
+
+// So nested builders can be passed straight to `key` and `push`
+impl From<JSONObjectBuilder> for JSONValue {
+    fn from(builder: JSONObjectBuilder) -> Self {
+        return builder.build();
+    }
+}
+
+impl From<JSONArrayBuilder> for JSONValue {
+    fn from(builder: JSONArrayBuilder) -> Self {
+        return builder.build();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+    use JSONValue::*;
+
+    #[test]
+    fn build_simple_object() {
+        let value = JSONBuilder::object()
+            .key("message", String("things are broken".into()))
+            .key("success", False)
+            .build();
+        assert_eq!(Ok(value), parse_json(fixtures::SIMPLE_OBJECT));
+    }
+
+    #[test]
+    fn build_nested_object() {
+        let value = JSONBuilder::object()
+            .key("data", JSONBuilder::object().key("number", Integer(1)))
+            .build();
+        assert_eq!(Ok(value), parse_json(fixtures::NESTED_OBJECT));
+    }
+
+    #[test]
+    fn build_array() {
+        let value = JSONBuilder::array()
+            .push(True)
+            .push(JSONBuilder::array())
+            .push(JSONBuilder::object())
+            .build();
+        assert_eq!(Ok(value), parse_json("[true, [], {}]"));
+    }
+
+    #[test]
+    fn object_and_array_helpers() {
+        let value = object([
+            ("message", String("things are broken".into())),
+            ("success", False),
+        ]);
+        assert_eq!(Ok(value), parse_json(fixtures::SIMPLE_OBJECT));
+        assert_eq!(Ok(array([1i64, 2, 3])), parse_json(fixtures::NUMBERS_ARRAY));
+
+        let nested = object([("data", object([("number", 1i64)]))]);
+        assert_eq!(Ok(nested), parse_json(fixtures::NESTED_OBJECT));
+        assert_eq!(
+            array([
+                array(Vec::<JSONValue>::new()),
+                object(Vec::<(&str, JSONValue)>::new())
+            ]),
+            Array(vec![Array(vec![]), Object(vec![])])
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::JSONValue;
+
+impl From<&str> for JSONValue {
+    fn from(string: &str) -> Self {
+        return JSONValue::String(string.into());
+    }
+}
+
+impl From<String> for JSONValue {
+    fn from(string: String) -> Self {
+        return JSONValue::String(string.into());
+    }
+}
+
+impl From<f64> for JSONValue {
+    fn from(number: f64) -> Self {
+        return JSONValue::Number(number);
+    }
+}
+
+/// Converts to an `Integer`, which keeps the value exact
+impl From<i64> for JSONValue {
+    fn from(integer: i64) -> Self {
+        return JSONValue::Integer(integer);
+    }
+}
+
+impl From<bool> for JSONValue {
+    fn from(boolean: bool) -> Self {
+        return if boolean {
+            JSONValue::True
+        } else {
+            JSONValue::False
+        };
+    }
+}
+
+impl From<()> for JSONValue {
+    fn from(_: ()) -> Self {
+        return JSONValue::Null;
+    }
+}
+
+impl From<Vec<JSONValue>> for JSONValue {
+    fn from(items: Vec<JSONValue>) -> Self {
+        return JSONValue::Array(items);
+    }
+}
+
+/// Collects key-value pairs into an `Object`, keeping their order
+impl FromIterator<(String, JSONValue)> for JSONValue {
+    fn from_iter<I: IntoIterator<Item = (String, JSONValue)>>(members: I) -> Self {
+        return JSONValue::Object(
+            members
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+
+    fn object(members: Vec<(&str, JSONValue)>) -> JSONValue {
+        return members
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+    }
+
+    #[test]
+    fn from_scalars() {
+        assert_eq!(JSONValue::from("hey!"), JSONValue::String("hey!".into()));
+        assert_eq!(JSONValue::from("hey!".to_string()), "hey!".into());
+        assert_eq!(JSONValue::from(1.5), JSONValue::Number(1.5));
+        assert_eq!(JSONValue::from(7i64), JSONValue::Integer(7));
+        assert_eq!(JSONValue::from(true), JSONValue::True);
+        assert_eq!(JSONValue::from(false), JSONValue::False);
+        assert_eq!(JSONValue::from(()), JSONValue::Null);
+    }
+
+    #[test]
+    fn from_into_kitchen_sink() {
+        let items: Vec<JSONValue> = vec![
+            object(vec![("type", "item thingo".into())]),
+            true.into(),
+            "hey!".into(),
+            vec![false.into(), true.into()].into(),
+        ];
+        let value = object(vec![(
+            "object",
+            object(vec![
+                ("thing", 1i64.into()),
+                ("another", 2.0e10.into()),
+                ("true", false.into()),
+                ("exists", ().into()),
+                ("items", items.into()),
+            ]),
+        )]);
+        assert_eq!(Ok(value), parse_json(fixtures::KITCHEN_SINK));
+    }
+}
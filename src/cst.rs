@@ -0,0 +1,353 @@
+use std::fmt;
+
+use crate::{parse_json_with_options, JSONParseResult, JSONValue, ParseOptions, Parser, Span};
+
+/// What a [`CstNode::Token`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    String,
+    /// Any number, however it's written
+    Number,
+    True,
+    False,
+    Null,
+    LeftBracket,
+    RightBracket,
+    LeftBrace,
+    RightBrace,
+    Colon,
+    Comma,
+    /// A run of spaces, tabs, newlines and carriage returns
+    Whitespace,
+    /// A `//` comment, not including the newline that ends it
+    LineComment,
+    /// A `/* */` comment
+    BlockComment,
+    /// A UTF-8 byte order mark at the very start of the input
+    ByteOrderMark,
+}
+
+/// What a [`CstNode::Tree`] holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeKind {
+    /// The whole input: the value along with everything around it
+    Document,
+    /// An array's brackets, and its items, commas and trivia between them
+    Array,
+    /// An object's braces, and its members, commas and trivia between them
+    Object,
+    /// A member's key, colon and value, and the trivia between them
+    Member,
+}
+
+/// A concrete syntax tree, which unlike a `JSONValue` keeps every byte of
+/// the input, comments and whitespace included, so a document can be
+/// changed without losing how it was written. Displaying a node gives back
+/// exactly the text it was parsed from
+#[derive(Debug, Clone, PartialEq)]
+pub enum CstNode {
+    /// A single token, along with its text exactly as written
+    Token {
+        kind: TokenKind,
+        text: String,
+        span: Span,
+    },
+    /// Tokens and smaller trees, in the order they're written
+    Tree {
+        kind: TreeKind,
+        children: Vec<CstNode>,
+        span: Span,
+    },
+}
+
+impl CstNode {
+    pub fn span(&self) -> Span {
+        return match self {
+            CstNode::Token { span, .. } | CstNode::Tree { span, .. } => *span,
+        };
+    }
+
+    /// The nodes in a tree, or nothing for a token
+    pub fn children(&self) -> &[CstNode] {
+        return match self {
+            CstNode::Token { .. } => &[],
+            CstNode::Tree { children, .. } => children,
+        };
+    }
+
+    /// Whether this is whitespace, a comment or a byte order mark, which
+    /// don't change the value a document holds
+    pub fn is_trivia(&self) -> bool {
+        return matches!(
+            self,
+            CstNode::Token {
+                kind: TokenKind::Whitespace
+                    | TokenKind::LineComment
+                    | TokenKind::BlockComment
+                    | TokenKind::ByteOrderMark,
+                ..
+            }
+        );
+    }
+}
+
+/// Writes out the source text the node was parsed from
+impl fmt::Display for CstNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            CstNode::Token { text, .. } => f.write_str(text),
+            CstNode::Tree { children, .. } => children.iter().try_for_each(|child| child.fmt(f)),
+        };
+    }
+}
+
+/// Parses a single JSON document into a concrete syntax tree, for tools like
+/// formatters that have to keep comments and layout. Comments are allowed,
+/// and otherwise it accepts and fails on the same input as
+/// `parse_json_with_options` with the default options, since that's what
+/// checks the input before the tree is built
+pub fn parse_json_cst(string: &str) -> JSONParseResult<CstNode> {
+    let options = ParseOptions {
+        allow_comments: true,
+        ..ParseOptions::default()
+    };
+    parse_json_with_options(string, &options)?;
+
+    let builder = CstBuilder {
+        parser: Parser::new(string, options),
+    };
+    let mut children = vec![];
+    let content_start = builder.parser.content_start();
+    if content_start > 0 {
+        children.push(builder.token(TokenKind::ByteOrderMark, 0, content_start));
+    }
+    let value_start = builder.trivia(content_start, &mut children);
+    let (value_end, value) = builder.value(value_start)?;
+    children.push(value);
+    builder.trivia(value_end, &mut children);
+
+    return Ok(builder.tree(TreeKind::Document, children));
+}
+
+/// Builds the tree for input that's already known to be valid, so it only
+/// has to find where each token ends
+struct CstBuilder<'a> {
+    parser: Parser<'a>,
+}
+
+impl CstBuilder<'_> {
+    /// The token from `start` up to just before `end`
+    fn token(&self, kind: TokenKind, start: usize, end: usize) -> CstNode {
+        return CstNode::Token {
+            kind,
+            text: self.parser.input[start..end].to_string(),
+            span: Span { start, end },
+        };
+    }
+
+    /// The tree holding `children`, spanning from the first of them to the
+    /// last
+    fn tree(&self, kind: TreeKind, children: Vec<CstNode>) -> CstNode {
+        let span = match (children.first(), children.last()) {
+            (Some(first), Some(last)) => Span {
+                start: first.span().start,
+                end: last.span().end,
+            },
+            _ => Span { start: 0, end: 0 },
+        };
+        return CstNode::Tree {
+            kind,
+            children,
+            span,
+        };
+    }
+
+    /// Adds a token for each run of whitespace and each comment from `from`
+    /// on, returning the position just after them
+    fn trivia(&self, from: usize, nodes: &mut Vec<CstNode>) -> usize {
+        let bytes = self.parser.bytes;
+        let mut i = from;
+        loop {
+            let (kind, end) = match bytes.get(i) {
+                Some(b' ' | b'\t' | b'\n' | b'\r') => {
+                    let run = bytes[i..]
+                        .iter()
+                        .take_while(|byte| matches!(byte, b' ' | b'\t' | b'\n' | b'\r'))
+                        .count();
+                    (TokenKind::Whitespace, i + run)
+                }
+                Some(&b'/') => match self.parser.skip_comment(i) {
+                    Some(comment_end) if bytes[i + 1] == b'/' => {
+                        // The newline is left to the whitespace after it
+                        let newline = bytes[comment_end - 1] == b'\n';
+                        (TokenKind::LineComment, comment_end - newline as usize)
+                    }
+                    Some(comment_end) => (TokenKind::BlockComment, comment_end),
+                    None => return i,
+                },
+                _ => return i,
+            };
+            nodes.push(self.token(kind, i, end));
+            i = end;
+        }
+    }
+
+    /// Given the position of a value, returns the position just after it
+    /// and its node
+    fn value(&self, from: usize) -> JSONParseResult<(usize, CstNode)> {
+        return match self.parser.bytes.get(from) {
+            Some(&b'[') => self.container(from, TreeKind::Array),
+            Some(&b'{') => self.container(from, TreeKind::Object),
+            _ => {
+                let (end_index, scalar) = self.parser.parse_json_scalar(from)?;
+                let kind = match scalar {
+                    JSONValue::String(_) => TokenKind::String,
+                    JSONValue::True => TokenKind::True,
+                    JSONValue::False => TokenKind::False,
+                    JSONValue::Null => TokenKind::Null,
+                    _ => TokenKind::Number,
+                };
+                Ok((end_index + 1, self.token(kind, from, end_index + 1)))
+            }
+        };
+    }
+
+    /// Given the position of an array's `[` or an object's `{`, returns the
+    /// position just after its end and its node
+    fn container(&self, from: usize, kind: TreeKind) -> JSONParseResult<(usize, CstNode)> {
+        let (start_kind, end_kind, end_byte) = match kind {
+            TreeKind::Object => (TokenKind::LeftBrace, TokenKind::RightBrace, b'}'),
+            _ => (TokenKind::LeftBracket, TokenKind::RightBracket, b']'),
+        };
+        let mut children = vec![self.token(start_kind, from, from + 1)];
+        let mut i = self.trivia(from + 1, &mut children);
+
+        while self.parser.bytes[i] != end_byte {
+            let (item_end, item) = match kind {
+                TreeKind::Object => self.member(i)?,
+                _ => self.value(i)?,
+            };
+            children.push(item);
+            i = self.trivia(item_end, &mut children);
+            if self.parser.bytes[i] == b',' {
+                children.push(self.token(TokenKind::Comma, i, i + 1));
+                i = self.trivia(i + 1, &mut children);
+            }
+        }
+
+        children.push(self.token(end_kind, i, i + 1));
+        return Ok((i + 1, self.tree(kind, children)));
+    }
+
+    /// Given the position of a member's key, returns the position just after
+    /// its value and its node
+    fn member(&self, from: usize) -> JSONParseResult<(usize, CstNode)> {
+        let (key_end_index, _) = self.parser.parse_json_string(from)?;
+        let mut children = vec![self.token(TokenKind::String, from, key_end_index + 1)];
+        let colon = self.trivia(key_end_index + 1, &mut children);
+        children.push(self.token(TokenKind::Colon, colon, colon + 1));
+        let value_start = self.trivia(colon + 1, &mut children);
+        let (value_end, value) = self.value(value_start)?;
+        children.push(value);
+        return Ok((value_end, self.tree(TreeKind::Member, children)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, ParseJSONErrorKind};
+
+    const COMMENTED: &str = "\u{FEFF}// settings\n{\n  \"name\": /* inline */ \"pkler\" , // trailing\n  \"list\" : [1, -2.5e3,\ttrue ,null],\r\n  \"empty\": {}\n}\n/* done */";
+
+    #[test]
+    fn parse_json_cst_round_trips_bytes() {
+        for input in [
+            COMMENTED,
+            fixtures::KITCHEN_SINK,
+            fixtures::SIMPLE_OBJECT,
+            " 1 ",
+            "[]",
+        ] {
+            let cst = parse_json_cst(input).unwrap();
+            assert_eq!(cst.to_string(), input);
+            assert_eq!(
+                cst.span(),
+                Span {
+                    start: 0,
+                    end: input.len()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn parse_json_cst_structure() {
+        let cst = parse_json_cst(
+            r#"{"a" : 1, // one
+"b": [true]}"#,
+        )
+        .unwrap();
+        let object = &cst.children()[0];
+        assert!(matches!(
+            object,
+            CstNode::Tree {
+                kind: TreeKind::Object,
+                ..
+            }
+        ));
+
+        let kinds = |node: &CstNode| -> Vec<std::string::String> {
+            return node
+                .children()
+                .iter()
+                .map(|child| match child {
+                    CstNode::Token { kind, .. } => format!("{:?}", kind),
+                    CstNode::Tree { kind, .. } => format!("{:?}", kind),
+                })
+                .collect();
+        };
+        assert_eq!(
+            kinds(object),
+            [
+                "LeftBrace",
+                "Member",
+                "Comma",
+                "Whitespace",
+                "LineComment",
+                "Whitespace",
+                "Member",
+                "RightBrace"
+            ]
+        );
+        let first_member = &object.children()[1];
+        assert_eq!(
+            kinds(first_member),
+            ["String", "Whitespace", "Colon", "Whitespace", "Number"]
+        );
+        assert_eq!(first_member.span(), Span { start: 1, end: 8 });
+        assert_eq!(first_member.to_string(), r#""a" : 1"#);
+        assert!(object.children()[4].is_trivia());
+        assert!(!first_member.is_trivia());
+    }
+
+    #[test]
+    fn parse_json_cst_errors_like_parse_json() {
+        for (input, kind) in [
+            ("", ParseJSONErrorKind::EmptyInput),
+            ("[1,]", ParseJSONErrorKind::NoValue),
+            (
+                "[1 2]",
+                ParseJSONErrorKind::UnexpectedChar {
+                    found: Some('2'),
+                    expected: ']',
+                },
+            ),
+            ("{} x", ParseJSONErrorKind::TrailingCharacters),
+            ("1 /* x", ParseJSONErrorKind::UnclosedComment),
+        ] {
+            let error = parse_json_cst(input).unwrap_err();
+            assert_eq!(error.kind(), &kind, "{}", input);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashSet;
+
+use crate::JSONValue;
+
+/// One difference found by [`json_diff`], at the JSON Pointer `path`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub path: String,
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    /// Only the second value has something at the path
+    Added(JSONValue),
+    /// Only the first value has something at the path
+    Removed(JSONValue),
+    /// Both have something at the path, but they differ and can't be
+    /// compared further, like two scalars or an array and an object
+    Modified { old: JSONValue, new: JSONValue },
+}
+
+/// Lists what changed going from `a` to `b`. Objects are compared member by
+/// member by key, in `a`'s order then `b`'s, and arrays item by item by
+/// index, with the extra items of the longer one being added or removed.
+/// When a key is repeated, only its first member is compared
+pub fn json_diff(a: &JSONValue, b: &JSONValue) -> Vec<Change> {
+    let mut changes = vec![];
+    diff_into(a, b, &mut String::new(), &mut changes);
+    return changes;
+}
+
+fn diff_into(a: &JSONValue, b: &JSONValue, path: &mut String, changes: &mut Vec<Change>) {
+    match (a, b) {
+        (JSONValue::Object(members), JSONValue::Object(other_members)) => {
+            let mut seen = HashSet::new();
+            for (key, value) in members {
+                if !seen.insert(key.as_str()) {
+                    continue;
+                }
+                let path_len = push_segment(path, key);
+                match b.get(key) {
+                    Some(other_value) => diff_into(value, other_value, path, changes),
+                    None => push_change(changes, path, ChangeKind::Removed(value.clone())),
+                }
+                path.truncate(path_len);
+            }
+
+            for (key, value) in other_members {
+                if !seen.insert(key.as_str()) {
+                    continue;
+                }
+                let path_len = push_segment(path, key);
+                push_change(changes, path, ChangeKind::Added(value.clone()));
+                path.truncate(path_len);
+            }
+        }
+        (JSONValue::Array(items), JSONValue::Array(other_items)) => {
+            for index in 0..items.len().max(other_items.len()) {
+                let path_len = push_segment(path, &index.to_string());
+                match (items.get(index), other_items.get(index)) {
+                    (Some(item), Some(other_item)) => diff_into(item, other_item, path, changes),
+                    (Some(item), None) => {
+                        push_change(changes, path, ChangeKind::Removed(item.clone()))
+                    }
+                    (None, Some(other_item)) => {
+                        push_change(changes, path, ChangeKind::Added(other_item.clone()))
+                    }
+                    (None, None) => unreachable!(),
+                }
+                path.truncate(path_len);
+            }
+        }
+        _ if a == b => {}
+        _ => push_change(
+            changes,
+            path,
+            ChangeKind::Modified {
+                old: a.clone(),
+                new: b.clone(),
+            },
+        ),
+    }
+}
+
+fn push_change(changes: &mut Vec<Change>, path: &str, kind: ChangeKind) {
+    changes.push(Change {
+        path: path.to_string(),
+        kind,
+    });
+}
+
+/// Adds `segment` to the end of a JSON Pointer, escaping `~` and `/`, and
+/// returns the length the pointer had before
+pub(crate) fn push_segment(path: &mut String, segment: &str) -> usize {
+    let path_len = path.len();
+    path.push('/');
+    path.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+    return path_len;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+    use JSONValue::*;
+
+    fn change(path: &str, kind: ChangeKind) -> Change {
+        return Change {
+            path: path.to_string(),
+            kind,
+        };
+    }
+
+    #[test]
+    fn diff_simple_object() {
+        let old = parse_json(fixtures::SIMPLE_OBJECT).unwrap();
+        let new = parse_json(r#"{ "message": "things are fixed", "success": false, "retries": 2}"#)
+            .unwrap();
+        assert_eq!(
+            json_diff(&old, &new),
+            vec![
+                change(
+                    "/message",
+                    ChangeKind::Modified {
+                        old: "things are broken".into(),
+                        new: "things are fixed".into()
+                    }
+                ),
+                change("/retries", ChangeKind::Added(Integer(2))),
+            ]
+        );
+        assert_eq!(json_diff(&new, &new), vec![]);
+    }
+
+    #[test]
+    fn diff_nested_and_arrays() {
+        let old = parse_json(r#"{"a": [1, 2, 3], "b/c": {"d~": true}, "e": 1}"#).unwrap();
+        let new = parse_json(r#"{"a": [1, 5], "b/c": {"d~": false}, "e": [1]}"#).unwrap();
+        assert_eq!(
+            json_diff(&old, &new),
+            vec![
+                change(
+                    "/a/1",
+                    ChangeKind::Modified {
+                        old: Integer(2),
+                        new: Integer(5)
+                    }
+                ),
+                change("/a/2", ChangeKind::Removed(Integer(3))),
+                change(
+                    "/b~1c/d~0",
+                    ChangeKind::Modified {
+                        old: True,
+                        new: False
+                    }
+                ),
+                change(
+                    "/e",
+                    ChangeKind::Modified {
+                        old: Integer(1),
+                        new: Array(vec![Integer(1)])
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            json_diff(&Null, &True),
+            vec![change(
+                "",
+                ChangeKind::Modified {
+                    old: Null,
+                    new: True
+                }
+            )]
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use std::fmt;
+
+/// The kind of problem a [`ParseJSONError`] describes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseJSONErrorKind {
+    /// A string was opened but never closed
+    MissingEndQuote,
+    /// A backslash in a string was followed by something other than a
+    /// JSON escape
+    InvalidEscape,
+    /// A string held a raw control character, U+0000 through U+001F
+    UnescapedControlCharacter,
+    /// A `\u` escape wasn't followed by four hex digits
+    InvalidUnicodeEscape,
+    /// A `\u` escape held one half of a surrogate pair without the other
+    UnpairedSurrogate,
+    InvalidNumber,
+    /// A `-` wasn't followed by the digits of a number
+    ExpectedDigitAfterMinus,
+    /// `NaN`, `Infinity` or `-Infinity` appeared without being allowed
+    NonFiniteNumber,
+    /// A number was too big for an `f64`, which the parse options forbid
+    NumberOutOfRange,
+    /// Something starting like `null`, `true` or `false` wasn't one of them
+    UnexpectedLiteral,
+    /// Nothing that could start a JSON value was found
+    NoValue,
+    UnexpectedComma,
+    /// `found` appeared where `expected` was needed, with `found` being
+    /// `None` at the end of the input
+    UnexpectedChar {
+        found: Option<char>,
+        expected: char,
+    },
+    MissingArrayEnd,
+    MissingObjectEnd,
+    /// The input ended where more of a value was needed
+    UnexpectedEof,
+    /// A `/*` comment was never closed
+    UnclosedComment,
+    /// The input was empty, or held nothing but whitespace and comments
+    EmptyInput,
+    /// The top-level value wasn't an object or array, which the parse
+    /// options require
+    ExpectedContainer,
+    /// Something other than whitespace followed the top-level value
+    TrailingCharacters,
+    /// An object had `key` more than once, which the parse options forbid
+    DuplicateKey {
+        key: String,
+    },
+    /// Arrays and objects were nested deeper than the parse options allow
+    MaxDepthExceeded,
+    /// More values were parsed than the parse options allow
+    MaxElementsExceeded,
+    /// An object had more members than the parse options allow
+    MaxObjectMembersExceeded,
+    /// A string was longer than the parse options allow
+    StringTooLong,
+    /// Reading the input failed, with the kind and message of the
+    /// underlying I/O error
+    Io {
+        kind: std::io::ErrorKind,
+        message: String,
+    },
+    /// The input read wasn't valid UTF-8
+    InvalidUtf8,
+    /// A JSON Pointer didn't start with `/`
+    InvalidPointer,
+    /// A JSONPath query couldn't be read, with the position being a byte
+    /// offset into the query
+    InvalidQuery,
+    /// A path used to build a value went through a scalar, used a key on an
+    /// array, skipped past the end of an array or set the same value twice.
+    /// The position is a byte offset into the path rather than an input
+    PathConflict,
+    /// A path pointed at nothing, with the position being the byte offset
+    /// into the path of the segment that wasn't found
+    PathNotFound,
+    /// A JSON Patch wasn't an array of operations, or an operation was
+    /// missing a member it needs. There's no input to point into, so the
+    /// position is always 0
+    InvalidPatch,
+    /// A JSON Patch `test` operation found a different value at its path
+    PatchTestFailed,
+}
+
+impl fmt::Display for ParseJSONErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            ParseJSONErrorKind::MissingEndQuote => "Missing end quotes for string",
+            ParseJSONErrorKind::InvalidEscape => "Invalid escape sequence",
+            ParseJSONErrorKind::UnescapedControlCharacter => {
+                "Unescaped control character in string"
+            }
+            ParseJSONErrorKind::InvalidUnicodeEscape => "Invalid unicode escape",
+            ParseJSONErrorKind::UnpairedSurrogate => "Unpaired surrogate in unicode escape",
+            ParseJSONErrorKind::InvalidNumber => "Invalid number",
+            ParseJSONErrorKind::ExpectedDigitAfterMinus => "Expected digit after '-'",
+            ParseJSONErrorKind::NonFiniteNumber => "NaN and Infinity are not allowed",
+            ParseJSONErrorKind::NumberOutOfRange => "Number out of range",
+            ParseJSONErrorKind::UnexpectedLiteral => "Unexpected literal",
+            ParseJSONErrorKind::NoValue => "No JSON value found",
+            ParseJSONErrorKind::UnexpectedComma => "Unexpected comma",
+            ParseJSONErrorKind::UnexpectedChar { expected, .. } => match expected {
+                ']' => "Expected ']' to end array",
+                '}' => "Expected '}' to end object",
+                '"' => r#"Expected '"' for object key"#,
+                ':' => "Expected ':' after object key",
+                expected => return write!(f, "Expected '{}'", expected),
+            },
+            ParseJSONErrorKind::MissingArrayEnd => "Missing closing bracket ']' for array",
+            ParseJSONErrorKind::MissingObjectEnd => "Missing closing brace '}' for object",
+            ParseJSONErrorKind::UnexpectedEof => "Unexpected end of input",
+            ParseJSONErrorKind::UnclosedComment => "Unclosed block comment",
+            ParseJSONErrorKind::EmptyInput => "Empty input",
+            ParseJSONErrorKind::ExpectedContainer => "Expected an object or array",
+            ParseJSONErrorKind::TrailingCharacters => "Unexpected trailing characters",
+            ParseJSONErrorKind::DuplicateKey { key } => {
+                return write!(f, "Duplicate key {:?} in object", key)
+            }
+            ParseJSONErrorKind::MaxDepthExceeded => "Maximum nesting depth exceeded",
+            ParseJSONErrorKind::MaxElementsExceeded => "Maximum number of values exceeded",
+            ParseJSONErrorKind::MaxObjectMembersExceeded => "Too many object members",
+            ParseJSONErrorKind::StringTooLong => "String exceeds maximum length",
+            ParseJSONErrorKind::Io { message, .. } => {
+                return write!(f, "Failed to read input: {}", message)
+            }
+            ParseJSONErrorKind::InvalidUtf8 => "Input is not valid UTF-8",
+            ParseJSONErrorKind::InvalidPointer => "JSON Pointer must start with '/'",
+            ParseJSONErrorKind::InvalidQuery => "Invalid JSONPath query",
+            ParseJSONErrorKind::PathConflict => "Path conflicts with an existing value",
+            ParseJSONErrorKind::PathNotFound => "No value at path",
+            ParseJSONErrorKind::InvalidPatch => "Invalid JSON Patch operation",
+            ParseJSONErrorKind::PatchTestFailed => "JSON Patch test failed",
+        };
+
+        return f.write_str(message);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseJSONError {
+    kind: ParseJSONErrorKind,
+    position: usize,
+    line: usize,
+    column: usize,
+}
+
+impl ParseJSONError {
+    /// Builds an error for a problem found at byte offset `position` in
+    /// `input`, working out the line and column it falls on
+    pub(crate) fn new(kind: ParseJSONErrorKind, input: &[u8], position: usize) -> Self {
+        let before = &input[..position.min(input.len())];
+        let line_start = before
+            .iter()
+            .rposition(|byte| byte == &b'\n')
+            .map_or(0, |newline_index| newline_index + 1);
+
+        // Columns count chars, so UTF-8 continuation bytes are skipped
+        let column = before[line_start..]
+            .iter()
+            .filter(|byte| (**byte & 0xC0) != 0x80)
+            .count();
+
+        return ParseJSONError {
+            kind,
+            position,
+            line: before.iter().filter(|byte| byte == &&b'\n').count() + 1,
+            column: column + 1,
+        };
+    }
+
+    /// The same error moved to `position`, for input that's indexed some
+    /// other way than the bytes its line and column were counted over
+    pub(crate) fn at_position(self, position: usize) -> Self {
+        return ParseJSONError { position, ..self };
+    }
+
+    pub fn kind(&self) -> &ParseJSONErrorKind {
+        return &self.kind;
+    }
+
+    /// The byte offset into the input where the problem was found, or the
+    /// char index for `parse_json_value`
+    pub fn position(&self) -> usize {
+        return self.position;
+    }
+
+    /// The 1-based line the problem was found on
+    pub fn line(&self) -> usize {
+        return self.line;
+    }
+
+    /// The 1-based column, in chars, the problem was found at
+    pub fn column(&self) -> usize {
+        return self.column;
+    }
+}
+
+impl fmt::Display for ParseJSONError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(
+            f,
+            "{} at line {}, column {}",
+            self.kind, self.line, self.column
+        );
+    }
+}
+
+impl std::error::Error for ParseJSONError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(input: &str) -> &[u8] {
+        return input.as_bytes();
+    }
+
+    #[test]
+    fn display_includes_location() {
+        assert_eq!(
+            ParseJSONError::new(ParseJSONErrorKind::InvalidNumber, bytes("[1, -]"), 4).to_string(),
+            "Invalid number at line 1, column 5"
+        );
+    }
+
+    #[test]
+    fn location_after_newlines() {
+        let err = ParseJSONError::new(ParseJSONErrorKind::NoValue, bytes("[\n  1,\n  ]"), 9);
+        assert_eq!((err.line(), err.column()), (3, 3));
+    }
+
+    #[test]
+    fn location_at_newline() {
+        let err = ParseJSONError::new(ParseJSONErrorKind::NoValue, bytes("[\n]"), 1);
+        assert_eq!((err.line(), err.column()), (1, 2));
+    }
+
+    #[test]
+    fn location_past_end_of_input() {
+        let err = ParseJSONError::new(ParseJSONErrorKind::MissingArrayEnd, bytes("[\n1"), 3);
+        assert_eq!((err.line(), err.column()), (2, 2));
+    }
+
+    #[test]
+    fn display_unexpected_char() {
+        let kind = ParseJSONErrorKind::UnexpectedChar {
+            found: Some('3'),
+            expected: ']',
+        };
+        assert_eq!(kind.to_string(), "Expected ']' to end array");
+    }
+
+    #[test]
+    fn display_unexpected_char_other_expected() {
+        let kind = ParseJSONErrorKind::UnexpectedChar {
+            found: None,
+            expected: ',',
+        };
+        assert_eq!(kind.to_string(), "Expected ','");
+    }
+
+    #[test]
+    fn converts_into_boxed_error() {
+        let err: Box<dyn std::error::Error> =
+            ParseJSONError::new(ParseJSONErrorKind::UnexpectedComma, bytes("[,]"), 1).into();
+        assert_eq!(err.to_string(), "Unexpected comma at line 1, column 2");
+    }
+}
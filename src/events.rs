@@ -0,0 +1,310 @@
+use crate::{JSONParseResult, JSONValue, ParseJSONError, ParseJSONErrorKind, ParseOptions, Parser};
+
+/// Something [`JSONEvents`] found in the input
+#[derive(Debug, Clone, PartialEq)]
+pub enum JSONEvent {
+    StartObject,
+    /// An object member's key, which its value follows
+    Key(String),
+    /// Anything but an array or object
+    Value(JSONValue),
+    EndObject,
+    StartArray,
+    EndArray,
+}
+
+/// A pull parser, which goes through a document one event at a time rather
+/// than building the whole tree, for documents too big to hold in memory as
+/// `JSONValue`s. It finds the same errors `parse_json_with_options` does,
+/// except that `duplicate_keys` and `intern_strings` are ignored, since
+/// keys and values are handed out as soon as they're found, so every member
+/// counts towards `max_object_members`. After an error, there are no more
+/// events
+pub struct JSONEvents<'a> {
+    parser: Parser<'a>,
+    position: usize,
+    /// Whether each enclosing container is an object
+    open: Vec<bool>,
+    /// How many members each enclosing object has had so far
+    member_counts: Vec<usize>,
+    state: State,
+}
+
+/// What the next event can be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// The top-level value, before anything has been read
+    Start,
+    /// A value, whether an array item or a member's
+    Value,
+    /// The first item or member of the container just started, or its end
+    First,
+    /// A member's key, after a comma
+    Key,
+    /// A comma or the end of the container, after an item or member
+    AfterItem,
+    /// An item or member, or the end when trailing commas are allowed
+    AfterComma,
+    Done,
+}
+
+impl<'a> JSONEvents<'a> {
+    /// Goes through `input` with the default options
+    pub fn new(input: &'a str) -> Self {
+        return JSONEvents::with_options(input, &ParseOptions::default());
+    }
+
+    pub fn with_options(input: &'a str, options: &ParseOptions) -> Self {
+        return JSONEvents {
+            parser: Parser::new(input, options.clone()),
+            position: 0,
+            open: vec![],
+            member_counts: vec![],
+            state: State::Start,
+        };
+    }
+
+    fn next_event(&mut self) -> JSONParseResult<Option<JSONEvent>> {
+        let parser = &mut self.parser;
+        let mut i = parser.skip_whitespace(self.position);
+        let in_object = self.open.last().copied();
+        let (end_byte, expected) = match in_object {
+            Some(true) => (b'}', '}'),
+            _ => (b']', ']'),
+        };
+
+        let state = match self.state {
+            State::Start => {
+                i = parser.skip_whitespace(parser.content_start());
+                let is_container = matches!(parser.bytes.get(i), Some(&b'[') | Some(&b'{'));
+                if parser.options.require_container && !is_container && i < parser.bytes.len() {
+                    return Err(parser.error(ParseJSONErrorKind::ExpectedContainer, i));
+                }
+                if i == parser.bytes.len() {
+                    return Err(parser.error(ParseJSONErrorKind::EmptyInput, i));
+                }
+                State::Value
+            }
+            State::First | State::AfterComma => {
+                let is_ok_to_end =
+                    self.state == State::First || parser.options.allow_trailing_commas;
+                if is_ok_to_end && parser.bytes.get(i) == Some(&end_byte) {
+                    return Ok(Some(self.end_container(i)));
+                }
+                match in_object {
+                    Some(true) => State::Key,
+                    _ => State::Value,
+                }
+            }
+            State::AfterItem if self.open.is_empty() => {
+                self.state = State::Done;
+                if i != parser.bytes.len() {
+                    return Err(parser.error(ParseJSONErrorKind::TrailingCharacters, i));
+                }
+                return Ok(None);
+            }
+            State::AfterItem => {
+                return match parser.bytes.get(i) {
+                    Some(&b',') => {
+                        self.position = i + 1;
+                        self.state = State::AfterComma;
+                        self.next_event()
+                    }
+                    Some(&byte) if byte == end_byte => Ok(Some(self.end_container(i))),
+                    None if in_object == Some(true) => {
+                        Err(parser.error(ParseJSONErrorKind::MissingObjectEnd, i))
+                    }
+                    None => Err(parser.error(ParseJSONErrorKind::MissingArrayEnd, i)),
+                    Some(_) => Err(parser.unexpected_char_error(expected, i)),
+                };
+            }
+            State::Done => return Ok(None),
+            state => state,
+        };
+
+        // A comma where an item or key should be, rather than a member's
+        // value, is a stray one
+        let is_item = state == State::Key || (state == State::Value && in_object == Some(false));
+        if is_item && parser.bytes.get(i) == Some(&b',') {
+            return Err(parser.error(ParseJSONErrorKind::UnexpectedComma, i));
+        }
+
+        if state == State::Key {
+            let (value_start, key, _) = parser.parse_json_member_key(i)?;
+            let member_count = self.member_counts.last_mut().unwrap();
+            *member_count += 1;
+            if matches!(parser.options.max_object_members, Some(max_members) if *member_count > max_members)
+            {
+                return Err(parser.error(ParseJSONErrorKind::MaxObjectMembersExceeded, i));
+            }
+            self.position = value_start;
+            self.state = State::Value;
+            return Ok(Some(JSONEvent::Key(key.into_owned())));
+        }
+
+        parser.count_element(i)?;
+        let event = match parser.bytes.get(i) {
+            Some(&b'[') | Some(&b'{') => {
+                parser.enter_container(i)?;
+                let is_object = parser.bytes[i] == b'{';
+                self.open.push(is_object);
+                if is_object {
+                    self.member_counts.push(0);
+                }
+                self.position = i + 1;
+                self.state = State::First;
+                if is_object {
+                    JSONEvent::StartObject
+                } else {
+                    JSONEvent::StartArray
+                }
+            }
+            _ => {
+                let (end_index, value) = parser.parse_json_scalar(i)?;
+                self.position = end_index + 1;
+                self.state = State::AfterItem;
+                JSONEvent::Value(value)
+            }
+        };
+        return Ok(Some(event));
+    }
+
+    /// Closes the innermost container, whose end is at `position`
+    fn end_container(&mut self, position: usize) -> JSONEvent {
+        self.parser.depth -= 1;
+        self.position = position + 1;
+        self.state = State::AfterItem;
+        return match self.open.pop() {
+            Some(true) => {
+                self.member_counts.pop();
+                JSONEvent::EndObject
+            }
+            _ => JSONEvent::EndArray,
+        };
+    }
+}
+
+impl Iterator for JSONEvents<'_> {
+    type Item = Result<JSONEvent, ParseJSONError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.next_event();
+        if event.is_err() {
+            self.state = State::Done;
+        }
+        return event.transpose();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+    use JSONEvent::*;
+
+    fn events(input: &str) -> Vec<JSONEvent> {
+        return JSONEvents::new(input).map(Result::unwrap).collect();
+    }
+
+    #[test]
+    fn events_simple_object() {
+        assert_eq!(
+            events(fixtures::SIMPLE_OBJECT),
+            vec![
+                StartObject,
+                Key("message".to_string()),
+                Value("things are broken".into()),
+                Key("success".to_string()),
+                Value(JSONValue::False),
+                EndObject,
+            ]
+        );
+    }
+
+    #[test]
+    fn events_nested() {
+        assert_eq!(
+            events(r#" [1, {"a": [], "b": {}}, "x"] "#),
+            vec![
+                StartArray,
+                Value(JSONValue::Integer(1)),
+                StartObject,
+                Key("a".to_string()),
+                StartArray,
+                EndArray,
+                Key("b".to_string()),
+                StartObject,
+                EndObject,
+                EndObject,
+                Value("x".into()),
+                EndArray,
+            ]
+        );
+        assert_eq!(events("null"), vec![Value(JSONValue::Null)]);
+        assert_eq!(events(fixtures::KITCHEN_SINK).len(), 26);
+    }
+
+    #[test]
+    fn events_errors_match_parse_json() {
+        for input in [
+            "",
+            "[",
+            "[1 2]",
+            "[1,]",
+            "[,1]",
+            "{,}",
+            r#"{"a" 1}"#,
+            r#"{"a": 1"#,
+            "{} x",
+            "[1]]",
+            "[tru]",
+            "[1,",
+            r#"{"a": 1,}"#,
+            "[01]",
+        ] {
+            let mut events = JSONEvents::new(input);
+            let error = events.find_map(Result::err);
+            assert_eq!(error, parse_json(input).err(), "{}", input);
+            assert_eq!(events.next(), None);
+        }
+    }
+
+    #[test]
+    fn events_with_options() {
+        let options = ParseOptions {
+            allow_trailing_commas: true,
+            allow_comments: true,
+            max_depth: Some(1),
+            ..ParseOptions::default()
+        };
+        let collected =
+            JSONEvents::with_options("[1, /* two */ 2,]", &options).collect::<Result<Vec<_>, _>>();
+        assert_eq!(
+            collected,
+            Ok(vec![
+                StartArray,
+                Value(JSONValue::Integer(1)),
+                Value(JSONValue::Integer(2)),
+                EndArray
+            ])
+        );
+        assert_eq!(
+            JSONEvents::with_options("[[]]", &options)
+                .find_map(Result::err)
+                .map(|error| error.kind().clone()),
+            Some(ParseJSONErrorKind::MaxDepthExceeded)
+        );
+    }
+
+    #[test]
+    fn events_max_object_members() {
+        let options = ParseOptions {
+            max_object_members: Some(2),
+            ..ParseOptions::default()
+        };
+        let input = r#"{"a": {"x": 1, "y": 2}, "b": 2, "c": 3}"#;
+        let error = JSONEvents::with_options(input, &options).find_map(Result::err);
+        assert_eq!(error, crate::parse_json_with_options(input, &options).err());
+        assert_eq!(error.unwrap().position(), 32);
+    }
+}
@@ -0,0 +1,47 @@
+//! Documents shared between the test modules
+
+pub const SIMPLE_OBJECT: &str = r#"{ "message": "things are broken", "success": false}"#;
+
+pub const NUMBERS_ARRAY: &str = "[ 1 , 2 , 3 ]";
+
+pub const NESTED_OBJECT: &str = r#"{
+    "data": {
+        "number": 1
+    }
+}"#;
+
+pub const KITCHEN_SINK: &str = r#"{
+    "object": {
+        "thing": 1,
+        "another": 2.0e10,
+        "true": false,
+        "exists": null,
+        "items": [
+            {
+                "type": "item thingo"
+            },
+            true,
+            "hey!",
+            [
+                false,
+                true
+            ]
+        ]
+    }
+}"#;
+
+pub const WITH_SECRETS: &str = r#"{
+    "user": {
+        "name": "soorria",
+        "password": "hunter2",
+        "sessions": [
+            {
+                "Password": {
+                    "hash": "abc"
+                },
+                "token": "xyz"
+            },
+            "password"
+        ]
+    }
+}"#;
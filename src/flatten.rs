@@ -0,0 +1,292 @@
+use crate::diff::push_segment;
+use crate::value::parse_array_index;
+use crate::{JSONValue, ParseJSONError, ParseJSONErrorKind};
+
+impl JSONValue {
+    /// Lists every scalar in the tree with the path to it, joining object
+    /// keys and array indices with dots like `object.items.0.type`. Empty
+    /// objects and arrays hold no scalars, so they don't appear, and a key
+    /// that contains a dot makes its paths ambiguous. A scalar at the top
+    /// level has an empty path
+    pub fn flatten(&self) -> Vec<(String, JSONValue)> {
+        let mut output = vec![];
+        self.flatten_into(&mut String::new(), &mut output);
+        return output;
+    }
+
+    /// Lists every scalar in the tree, borrowed, with the JSON Pointer (RFC
+    /// 6901) to it, like `/object/items/0/type`. Unlike `flatten`'s paths,
+    /// pointers escape `~` and `/` in keys, so they're never ambiguous.
+    /// Empty objects and arrays don't appear, and a scalar at the top level
+    /// has the empty pointer
+    pub fn leaves(&self) -> Vec<(String, &JSONValue)> {
+        let mut output = vec![];
+        self.leaves_into(&mut String::new(), &mut output);
+        return output;
+    }
+
+    /// Rebuilds a tree from dotted paths like the ones `flatten` gives. A
+    /// segment that's an array index makes an array when there isn't a value
+    /// there yet, and anything else makes an object, so an object whose
+    /// first key looks like an index comes back as an array. Items must be
+    /// added in order, since an index can only point at an existing item or
+    /// one past the end. With no pairs, the result is an empty object
+    pub fn unflatten(
+        pairs: impl IntoIterator<Item = (String, JSONValue)>,
+    ) -> Result<JSONValue, ParseJSONError> {
+        let mut root: Option<JSONValue> = None;
+
+        for (path, value) in pairs {
+            let conflict = |position: usize| {
+                return ParseJSONError::new(
+                    ParseJSONErrorKind::PathConflict,
+                    path.as_bytes(),
+                    position,
+                );
+            };
+
+            if path.is_empty() {
+                if root.is_some() {
+                    return Err(conflict(0));
+                }
+                root = Some(value);
+                continue;
+            }
+
+            let mut segments = vec![];
+            let mut offset = 0;
+            for segment in path.split('.') {
+                segments.push((offset, segment));
+                offset += segment.len() + 1;
+            }
+
+            let mut target = root.get_or_insert_with(|| container_for(segments[0].1));
+            let mut value = Some(value);
+            for (index, &(offset, segment)) in segments.iter().enumerate() {
+                // The value to put here if nothing is yet: the leaf itself at
+                // the end of the path, or a container for the next segment
+                let mut new_value = || match segments.get(index + 1) {
+                    Some(&(_, next)) => container_for(next),
+                    None => value.take().unwrap(),
+                };
+                let is_leaf = index + 1 == segments.len();
+
+                target = match target {
+                    JSONValue::Object(members) => {
+                        match members.iter().position(|(key, _)| key == segment) {
+                            Some(_) if is_leaf => return Err(conflict(offset)),
+                            Some(position) => &mut members[position].1,
+                            None => {
+                                members.push((segment.into(), new_value()));
+                                &mut members.last_mut().unwrap().1
+                            }
+                        }
+                    }
+                    JSONValue::Array(items) => match parse_array_index(segment) {
+                        Some(position) if position == items.len() => {
+                            items.push(new_value());
+                            items.last_mut().unwrap()
+                        }
+                        Some(position) if position < items.len() && !is_leaf => {
+                            &mut items[position]
+                        }
+                        _ => return Err(conflict(offset)),
+                    },
+                    _ => return Err(conflict(offset)),
+                };
+            }
+        }
+
+        return Ok(root.unwrap_or(JSONValue::Object(vec![])));
+    }
+
+    fn flatten_into(&self, path: &mut String, output: &mut Vec<(String, JSONValue)>) {
+        let mut flatten_child = |segment: &str, child: &JSONValue| {
+            let path_len = path.len();
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(segment);
+            child.flatten_into(path, output);
+            path.truncate(path_len);
+        };
+
+        match self {
+            JSONValue::Object(members) => {
+                for (key, value) in members {
+                    flatten_child(key, value);
+                }
+            }
+            JSONValue::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    flatten_child(&index.to_string(), item);
+                }
+            }
+            scalar => output.push((path.clone(), scalar.clone())),
+        }
+    }
+
+    fn leaves_into<'a>(&'a self, path: &mut String, output: &mut Vec<(String, &'a JSONValue)>) {
+        match self {
+            JSONValue::Object(members) => {
+                for (key, value) in members {
+                    let path_len = push_segment(path, key);
+                    value.leaves_into(path, output);
+                    path.truncate(path_len);
+                }
+            }
+            JSONValue::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    let path_len = push_segment(path, &index.to_string());
+                    item.leaves_into(path, output);
+                    path.truncate(path_len);
+                }
+            }
+            scalar => output.push((path.clone(), scalar)),
+        }
+    }
+}
+
+/// An empty container of the kind a path segment points into
+fn container_for(segment: &str) -> JSONValue {
+    return match parse_array_index(segment) {
+        Some(_) => JSONValue::Array(vec![]),
+        None => JSONValue::Object(vec![]),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+    use JSONValue::*;
+
+    #[test]
+    fn flatten_kitchen_sink() {
+        let flattened = parse_json(fixtures::KITCHEN_SINK).unwrap().flatten();
+        let paths = flattened
+            .iter()
+            .map(|(path, _)| path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "object.thing",
+                "object.another",
+                "object.true",
+                "object.exists",
+                "object.items.0.type",
+                "object.items.1",
+                "object.items.2",
+                "object.items.3.0",
+                "object.items.3.1",
+            ]
+        );
+        assert_eq!(
+            flattened[4],
+            (
+                "object.items.0.type".to_string(),
+                String("item thingo".into())
+            )
+        );
+        assert_eq!(flattened[3], ("object.exists".to_string(), Null));
+    }
+
+    #[test]
+    fn flatten_scalars_and_empty_containers() {
+        assert_eq!(Integer(1).flatten(), vec![("".to_string(), Integer(1))]);
+        assert_eq!(
+            parse_json(r#"{"a": {}, "b": [], "c": [true]}"#)
+                .unwrap()
+                .flatten(),
+            vec![("c.0".to_string(), True)]
+        );
+    }
+
+    #[test]
+    fn leaves_kitchen_sink() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let leaves = value.leaves();
+        assert_eq!(leaves.len(), 9);
+        for (pointer, leaf) in [
+            ("/object/thing", Integer(1)),
+            ("/object/another", Number(2.0e10)),
+            ("/object/exists", Null),
+            ("/object/items/0/type", String("item thingo".into())),
+            ("/object/items/2", String("hey!".into())),
+            ("/object/items/3/1", True),
+        ] {
+            assert!(
+                leaves.contains(&(pointer.to_string(), &leaf)),
+                "{}",
+                pointer
+            );
+        }
+        for (pointer, leaf) in &leaves {
+            assert_eq!(value.pointer(pointer), Some(*leaf));
+        }
+    }
+
+    #[test]
+    fn leaves_escapes_and_empty_containers() {
+        assert_eq!(Null.leaves(), vec![("".to_string(), &Null)]);
+        let value = parse_json(r#"{"a/b": {"m~n": 1}, "c": [], "d": {}}"#).unwrap();
+        assert_eq!(
+            value.leaves(),
+            vec![("/a~1b/m~0n".to_string(), &Integer(1))]
+        );
+    }
+
+    fn pairs(pairs: Vec<(&str, JSONValue)>) -> Vec<(std::string::String, JSONValue)> {
+        return pairs
+            .into_iter()
+            .map(|(path, value)| (path.to_string(), value))
+            .collect();
+    }
+
+    #[test]
+    fn unflatten_kitchen_sink_round_trip() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        assert_eq!(JSONValue::unflatten(value.flatten()), Ok(value));
+
+        let nested = parse_json(r#"[{"a": [[1], {"b": null}]}, "x"]"#).unwrap();
+        assert_eq!(JSONValue::unflatten(nested.flatten()), Ok(nested));
+    }
+
+    #[test]
+    fn unflatten_builds_objects_and_arrays() {
+        assert_eq!(
+            JSONValue::unflatten(pairs(vec![
+                ("a.b", Integer(1)),
+                ("a.list.0", True),
+                ("a.list.1.c", Null),
+                ("d", "e".into()),
+            ])),
+            parse_json(r#"{"a": {"b": 1, "list": [true, {"c": null}]}, "d": "e"}"#)
+        );
+        assert_eq!(JSONValue::unflatten(pairs(vec![("", False)])), Ok(False));
+        assert_eq!(JSONValue::unflatten(vec![]), Ok(Object(vec![])));
+    }
+
+    #[test]
+    fn unflatten_conflicts() {
+        let error =
+            JSONValue::unflatten(pairs(vec![("a", Integer(1)), ("a.b", Integer(2))])).unwrap_err();
+        assert_eq!(error.kind(), &ParseJSONErrorKind::PathConflict);
+        assert_eq!(error.position(), 2);
+
+        for conflicting in [
+            vec![("a.b", Integer(1)), ("a", Integer(2))],
+            vec![("a.b", Integer(1)), ("a.b", Integer(2))],
+            vec![("a.0", Integer(1)), ("a.x", Integer(2))],
+            vec![("a.1", Integer(1))],
+            vec![("", Integer(1)), ("", Integer(2))],
+            vec![("0", Integer(1)), ("", Integer(2))],
+        ] {
+            assert_eq!(
+                JSONValue::unflatten(pairs(conflicting)).unwrap_err().kind(),
+                &ParseJSONErrorKind::PathConflict
+            );
+        }
+    }
+}
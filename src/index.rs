@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use crate::{JSONString, JSONValue};
+
+/// A hash index over an object's keys, for looking up members in constant
+/// time rather than scanning them like `get` does. Members keep their order,
+/// since the index only records where each key is.
+///
+/// The index borrows the object, so the object can't be changed while the
+/// index is around. Adding, removing or reordering members moves them, so
+/// after changing the object, build a new index rather than keeping
+/// positions from the old one
+#[derive(Debug, Clone)]
+pub struct ObjectIndex<'a> {
+    members: &'a [(JSONString, JSONValue)],
+    positions: HashMap<&'a str, usize>,
+}
+
+impl<'a> ObjectIndex<'a> {
+    /// The value for `key`. When a key appears more than once, this finds
+    /// the first one, like `get` does
+    pub fn get(&self, key: &str) -> Option<&'a JSONValue> {
+        return self.position(key).map(|position| &self.members[position].1);
+    }
+
+    /// Where `key`'s member is in the object
+    pub fn position(&self, key: &str) -> Option<usize> {
+        return self.positions.get(key).copied();
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        return self.positions.contains_key(key);
+    }
+
+    /// How many distinct keys the object has
+    pub fn len(&self) -> usize {
+        return self.positions.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.positions.is_empty();
+    }
+
+    /// The object's members, in their original order
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a JSONValue)> {
+        return self
+            .members
+            .iter()
+            .map(|(key, value)| (key.as_str(), value));
+    }
+}
+
+impl JSONValue {
+    /// Builds an [`ObjectIndex`] over this object's keys, which is worth it
+    /// when looking up many keys in a large object. Anything but an object
+    /// has no keys to index, so gives `None`
+    pub fn index_object(&self) -> Option<ObjectIndex<'_>> {
+        let members = match self {
+            JSONValue::Object(members) => members,
+            _ => return None,
+        };
+
+        let mut positions = HashMap::with_capacity(members.len());
+        for (position, (key, _)) in members.iter().enumerate() {
+            positions.entry(key.as_str()).or_insert(position);
+        }
+        return Some(ObjectIndex { members, positions });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_json;
+
+    #[test]
+    fn index_large_object() {
+        let value: JSONValue = (0..10_000i64)
+            .map(|n| (format!("key{}", n), JSONValue::Integer(n * 2)))
+            .collect();
+        let index = value.index_object().unwrap();
+
+        assert_eq!(index.len(), 10_000);
+        for n in (0..10_000i64).step_by(97) {
+            let key = format!("key{}", n);
+            assert_eq!(index.get(&key), Some(&JSONValue::Integer(n * 2)));
+            assert_eq!(index.get(&key), value.get(&key));
+            assert_eq!(index.position(&key), Some(n as usize));
+        }
+        assert_eq!(index.get("key10000"), None);
+        assert!(!index.contains_key("missing"));
+        assert_eq!(index.iter().nth(3), Some(("key3", &JSONValue::Integer(6))));
+    }
+
+    #[test]
+    fn index_duplicates_and_non_objects() {
+        let value = parse_json(r#"{"a": 1, "b": 2, "a": 3}"#).unwrap();
+        let index = value.index_object().unwrap();
+        assert_eq!(index.get("a"), Some(&JSONValue::Integer(1)));
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.iter().count(), 3);
+
+        assert!(parse_json("{}").unwrap().index_object().unwrap().is_empty());
+        assert!(parse_json("[1]").unwrap().index_object().is_none());
+    }
+}
@@ -1,266 +1,1442 @@
+#![allow(clippy::needless_return)]
+
+mod borrowed;
+mod builder;
+mod convert;
+mod cst;
+mod diff;
+mod error;
+mod events;
+#[cfg(test)]
+mod fixtures;
+mod flatten;
+mod index;
+mod patch;
+mod query;
+mod schema;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod serialize;
+mod spanned;
+mod string;
+mod value;
+mod visit;
+
+pub use borrowed::{parse_json_borrowed, JSONValueRef};
+pub use builder::{array, object, JSONArrayBuilder, JSONBuilder, JSONObjectBuilder};
+pub use cst::{parse_json_cst, CstNode, TokenKind, TreeKind};
+pub use diff::{json_diff, Change, ChangeKind};
+pub use error::{ParseJSONError, ParseJSONErrorKind};
+pub use events::{JSONEvent, JSONEvents};
+pub use index::ObjectIndex;
+pub use serialize::{
+    escape_json_string, FloatStyle, NewlineStyle, PrettyOptions, SerializeOptions,
+};
+pub use spanned::{parse_json_spanned, Span, Spanned, SpannedValue};
+pub use string::JSONString;
+pub use value::{ArrayStrategy, ValueType};
+pub use visit::Visitor;
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::str::FromStr;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum JSONValue {
-    String(String),
+    String(JSONString),
     Number(f64),
-    Object(Vec<(String, JSONValue)>),
+    /// A number written without a fraction or exponent, when it fits in an
+    /// `i64`
+    Integer(i64),
+    /// A number exactly as it was written, which the parser gives instead
+    /// of `Integer` and `Number` when `preserve_number_text` is set. The
+    /// text always follows the JSON number grammar
+    RawNumber(String),
+    Object(Vec<(JSONString, JSONValue)>),
     Array(Vec<JSONValue>),
     True,
     False,
     Null,
 }
 
+type JSONParseResult<T> = Result<T, ParseJSONError>;
+
+/// The nesting depth `parse_json` allows
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Controls what `parse_json_with_options` accepts
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ParseJSONError(&'static str);
+pub struct ParseOptions {
+    /// How many arrays and objects may be nested inside each other, or
+    /// `None` for no limit. Parsing keeps nested levels on the heap rather
+    /// than recursing, but dropping, comparing or serializing a value does
+    /// recurse, so very deep values could otherwise overflow the stack
+    pub max_depth: Option<usize>,
+    /// Whether `//` line comments and `/* */` block comments are allowed
+    /// wherever whitespace is, as in JSONC files like `tsconfig.json`
+    pub allow_comments: bool,
+    /// Whether the last item of an array or member of an object may be
+    /// followed by a single comma
+    pub allow_trailing_commas: bool,
+    /// Whether `NaN`, `Infinity` and `-Infinity` are accepted as numbers, as
+    /// JavaScript and Python can write them
+    pub allow_non_finite: bool,
+    /// Whether strings and keys may be wrapped in single quotes, as in JSON5,
+    /// like `'hello'`. A `"` doesn't need escaping inside them, and `\'` is
+    /// an escape in either kind of string
+    pub allow_single_quotes: bool,
+    /// Whether object keys may be bare identifiers, as in JSON5 and
+    /// JavaScript object literals, like `{ name: "x" }`. An identifier is
+    /// ASCII letters, digits, `_` and `$`, not starting with a digit
+    pub allow_unquoted_keys: bool,
+    /// Whether integers may be written in hexadecimal, as in JSON5, like
+    /// `0xFF` or `-0x10`. They're parsed like other integers, so they're
+    /// never kept as `RawNumber`s
+    pub allow_hex_numbers: bool,
+    /// What to do when an object has the same key more than once
+    pub duplicate_keys: DuplicateKeyPolicy,
+    /// How many values may be parsed in total, counting every array,
+    /// object and scalar but not object keys, or `None` for no limit. This
+    /// bounds wide input the way `max_depth` bounds deep input
+    pub max_elements: Option<usize>,
+    /// How many members a single object may have, or `None` for no limit.
+    /// A member dropped for repeating a key doesn't count
+    pub max_object_members: Option<usize>,
+    /// How many bytes a string or key may take up between its quotes, as
+    /// it's written with escapes rather than once they're decoded, or `None`
+    /// for no limit. A string that's too long is given up on as soon as the
+    /// limit is passed, rather than being scanned to its end
+    pub max_string_length: Option<usize>,
+    /// Whether the top-level value has to be an object or array, as the
+    /// original JSON RFC required
+    pub require_container: bool,
+    /// Whether numbers are kept as `RawNumber`s holding the text they were
+    /// written as, so big or precise values like `1e1000` or prices can be
+    /// written out unchanged or parsed some other way
+    pub preserve_number_text: bool,
+    /// Whether a number too big for an `f64`, like `1e400`, is an error
+    /// rather than becoming an infinity
+    pub reject_non_finite_results: bool,
+    /// Whether string values holding the same text share one allocation,
+    /// which saves memory when values like enum names repeat a lot. Keys can
+    /// be shared too with `JSONValue::intern_keys`
+    pub intern_strings: bool,
+    /// What `parse_json_bytes` does with bytes that aren't UTF-8
+    pub on_invalid_utf8: InvalidUtf8,
+}
 
-type JSONParseResult<T> = Result<T, ParseJSONError>;
+impl ParseOptions {
+    /// Options that accept only what RFC 8259 allows, and reject what it
+    /// leaves up to the parser where accepting it could hide mistakes. On
+    /// top of the defaults, which already reject numbers with leading
+    /// zeros, unescaped control characters in strings, anything after the
+    /// top-level value, comments, trailing commas and `NaN` or infinities,
+    /// an object holding the same key twice is a `DuplicateKey` error and a
+    /// number too big for an `f64`, like `1e400`, is a `NumberOutOfRange`
+    /// error rather than an infinity. Bytes that aren't UTF-8 are an error,
+    /// and the depth limit is the default one
+    pub fn strict() -> Self {
+        return ParseOptions {
+            duplicate_keys: DuplicateKeyPolicy::Error,
+            reject_non_finite_results: true,
+            ..ParseOptions::default()
+        };
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        return ParseOptions {
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            allow_comments: false,
+            allow_trailing_commas: false,
+            allow_non_finite: false,
+            allow_single_quotes: false,
+            allow_unquoted_keys: false,
+            allow_hex_numbers: false,
+            duplicate_keys: DuplicateKeyPolicy::Keep,
+            max_elements: None,
+            max_object_members: None,
+            max_string_length: None,
+            require_container: false,
+            preserve_number_text: false,
+            reject_non_finite_results: false,
+            intern_strings: false,
+            on_invalid_utf8: InvalidUtf8::Error,
+        };
+    }
+}
+
+/// How `parse_json_with_options` handles an object key that appears more
+/// than once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKeyPolicy {
+    /// Keep every member, in the order they appear
+    Keep,
+    /// Keep only the first member with each key
+    First,
+    /// Keep only the last value given for each key, where the key first
+    /// appeared
+    Last,
+    /// Fail with a `DuplicateKey` error
+    Error,
+}
+
+/// How `parse_json_bytes` handles input that isn't valid UTF-8
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidUtf8 {
+    /// Fail with an `InvalidUtf8` error at the first bad byte
+    Error,
+    /// Replace each bad sequence with U+FFFD, the replacement character,
+    /// and parse the result. Positions in any error are then byte offsets
+    /// into the repaired text, which may not line up with the input's
+    Replace,
+}
+
+/// How the parser turns what it finds into values, so the same parsing code
+/// can build plain `JSONValue`s or values that know where they came from
+pub(crate) trait Build<'a> {
+    type Key;
+    type Value;
+
+    /// Builds a key, which borrows from the input when it has no escapes
+    fn key(key: Cow<'a, str>, span: Span) -> Self::Key;
+    /// Builds anything but an array or object. Strings only come here when
+    /// they're interned, and go to `string` otherwise
+    fn scalar(value: JSONValue, span: Span) -> Self::Value;
+    /// Builds a string value, which borrows from the input when it has no
+    /// escapes
+    fn string(string: Cow<'a, str>, span: Span) -> Self::Value {
+        return Self::scalar(JSONValue::String(string.into_owned().into()), span);
+    }
+    fn array(items: Vec<Self::Value>, span: Span) -> Self::Value;
+    fn object(members: Vec<(Self::Key, Self::Value)>, span: Span) -> Self::Value;
+}
+
+/// The members of an object, as built by `B`
+type Members<'a, B> = Vec<(<B as Build<'a>>::Key, <B as Build<'a>>::Value)>;
+
+/// An array or object the parser is partway through
+struct Frame<'a, B: Build<'a>> {
+    /// The position of its '[' or '{'
+    start: usize,
+    /// The position of the item or member being parsed
+    item_start: usize,
+    /// Whether the last item has been parsed, so only the end may follow
+    should_end: bool,
+    is_ok_to_end: bool,
+    contents: Contents<'a, B>,
+}
+
+impl<'a, B: Build<'a>> Frame<'a, B> {
+    fn new(start: usize, contents: Contents<'a, B>) -> Self {
+        return Frame {
+            start,
+            item_start: start,
+            should_end: false,
+            is_ok_to_end: true,
+            contents,
+        };
+    }
+}
+
+/// What an array or object the parser is partway through holds so far
+enum Contents<'a, B: Build<'a>> {
+    Array(Vec<B::Value>),
+    Object {
+        members: Members<'a, B>,
+        /// Where each key is in `members`, which is only needed when
+        /// duplicates aren't all kept
+        seen_keys: HashMap<Cow<'a, str>, usize>,
+        /// The key of the member whose value is being parsed
+        key: Option<(Cow<'a, str>, Span)>,
+    },
+}
+
+/// What starting to parse a value came to
+enum Started<'a, B: Build<'a>> {
+    /// A whole scalar, with the index it ends at once whitespace after it's
+    /// skipped
+    Scalar(usize, B::Value),
+    /// An array or object still to be parsed
+    Container(Frame<'a, B>),
+}
+
+/// What a container needs next
+enum Resume {
+    /// Its next value parsed, starting at this position
+    Value(usize),
+    /// Nothing, since it ended at this index
+    End(usize),
+}
+
+/// Builds plain `JSONValue`s, ignoring spans
+struct BuildJSONValue;
+
+impl<'a> Build<'a> for BuildJSONValue {
+    type Key = JSONString;
+    type Value = JSONValue;
 
-/// Given the chars, and position of the starting '"', returns
-/// the index of the end quote and the found string
-fn parse_json_string(chars: &Vec<char>, from: usize) -> JSONParseResult<(usize, String)> {
-    let mut i = from + 1;
-    let mut string_end_found = false;
+    fn key(key: Cow<'a, str>, _: Span) -> JSONString {
+        return JSONString::Owned(key.into_owned());
+    }
+
+    fn scalar(value: JSONValue, _: Span) -> JSONValue {
+        return value;
+    }
+
+    fn array(items: Vec<JSONValue>, _: Span) -> JSONValue {
+        return JSONValue::Array(items);
+    }
+
+    fn object(members: Vec<(JSONString, JSONValue)>, _: Span) -> JSONValue {
+        return JSONValue::Object(members);
+    }
+}
+
+/// The state of a single parse over some input. Positions are byte offsets
+/// into the input, which is scanned as bytes since everything structural in
+/// JSON is ASCII
+struct Parser<'a, B: Build<'a> = BuildJSONValue> {
+    input: &'a str,
+    bytes: &'a [u8],
+    options: ParseOptions,
+    /// How many arrays and objects enclose the value being parsed
+    depth: usize,
+    /// How many of those are objects
+    open_objects: usize,
+    /// How many values have been started so far
+    elements: usize,
+    /// The allocations string values share when `intern_strings` is set
+    interned: HashSet<Rc<str>>,
+    /// Whether errors are recorded in `errors` and skipped over where
+    /// possible, rather than ending the parse
+    recovering: bool,
+    errors: Vec<ParseJSONError>,
+    build: PhantomData<B>,
+}
+
+impl<'a, B: Build<'a>> Parser<'a, B> {
+    fn new(input: &'a str, options: ParseOptions) -> Self {
+        return Parser {
+            input,
+            bytes: input.as_bytes(),
+            options,
+            depth: 0,
+            open_objects: 0,
+            elements: 0,
+            interned: HashSet::new(),
+            recovering: false,
+            errors: vec![],
+            build: PhantomData,
+        };
+    }
+
+    fn error(&self, kind: ParseJSONErrorKind, position: usize) -> ParseJSONError {
+        // Whitespace skipping stops at a block comment that's never closed,
+        // so whatever went wrong there is down to the comment
+        let kind = if self.is_unclosed_comment(position) {
+            ParseJSONErrorKind::UnclosedComment
+        } else {
+            kind
+        };
+        return ParseJSONError::new(kind, self.bytes, position);
+    }
+
+    /// When recovering, records `error` so parsing can carry on past it, and
+    /// otherwise returns it to end the parse
+    fn recover(&mut self, error: ParseJSONError) -> JSONParseResult<()> {
+        if !self.recovering {
+            return Err(error);
+        }
+        self.errors.push(error);
+        return Ok(());
+    }
 
-    while let Some(ch) = chars.get(i) {
-        if ch == &'"' {
-            string_end_found = true;
-            break;
+    /// Like `recover`, but for the input ending inside an array or object,
+    /// which is only recorded once however many of them are left open
+    fn recover_at_end(&mut self, error: ParseJSONError) -> JSONParseResult<()> {
+        let is_recorded = matches!(
+            self.errors.last(),
+            Some(recorded) if recorded.position() >= self.bytes.len()
+        );
+        if is_recorded {
+            return Ok(());
         }
+        return self.recover(error);
+    }
 
-        if ch == &'\\' {
+    /// Given the position of something that couldn't be parsed, skips past
+    /// it to the next ',', ']' or '}' outside of any string, array or object
+    /// it holds, or to the end of the input
+    fn skip_junk(&self, from: usize) -> usize {
+        let mut depth = 0;
+        let mut in_string = false;
+        let mut i = from;
+        while let Some(&byte) = self.bytes.get(i) {
+            match byte {
+                b'\\' if in_string => i += 1,
+                b'"' => in_string = !in_string,
+                _ if in_string => {}
+                b'[' | b'{' => depth += 1,
+                b']' | b'}' if depth > 0 => depth -= 1,
+                b',' | b']' | b'}' => return i,
+                _ => {}
+            }
             i += 1;
         }
+        return self.bytes.len();
+    }
 
-        i += 1;
+    /// The error for finding something other than `expected` at `position`,
+    /// or for the input ending there
+    fn unexpected_char_error(&self, expected: char, position: usize) -> ParseJSONError {
+        let kind = match self.char_at(position) {
+            None => ParseJSONErrorKind::UnexpectedEof,
+            found => ParseJSONErrorKind::UnexpectedChar { found, expected },
+        };
+        return self.error(kind, position);
     }
 
-    if !string_end_found {
-        return Err(ParseJSONError("Missing end quotes for string"));
+    /// The char starting at `position`
+    fn char_at(&self, position: usize) -> Option<char> {
+        return self.input.get(position..)?.chars().next();
     }
 
-    return Ok((i, chars[from + 1..i].iter().collect()));
-}
+    /// Records entering the array or object starting at `from`, erroring
+    /// if that nests deeper than allowed, in which case it isn't entered
+    fn enter_container(&mut self, from: usize) -> JSONParseResult<()> {
+        if matches!(self.options.max_depth, Some(max_depth) if self.depth >= max_depth) {
+            return Err(self.error(ParseJSONErrorKind::MaxDepthExceeded, from));
+        }
+        self.depth += 1;
+        return Ok(());
+    }
 
-fn parse_json_number(chars: &Vec<char>, from: usize) -> JSONParseResult<(usize, f64)> {
-    // If the first char is a minus sign, let's just skip for simplicity
-    // in the loop below
-    let mut i = match chars.get(from) {
-        Some(ch) if ch == &'-' => from + 1,
-        _ => from,
-    };
+    /// Records starting the value at `from`, erroring if that's more values
+    /// than allowed
+    fn count_element(&mut self, from: usize) -> JSONParseResult<()> {
+        self.elements += 1;
+        if matches!(self.options.max_elements, Some(max_elements) if self.elements > max_elements) {
+            return Err(self.error(ParseJSONErrorKind::MaxElementsExceeded, from));
+        }
+        return Ok(());
+    }
+
+    /// Whether a string can start at `position`
+    fn is_quote(&self, position: usize) -> bool {
+        return match self.bytes.get(position) {
+            Some(&b'"') => true,
+            Some(&b'\'') => self.options.allow_single_quotes,
+            _ => false,
+        };
+    }
+
+    /// Given the position of the starting quote, returns
+    /// the index of the end quote and the found string
+    fn parse_json_string(&self, from: usize) -> JSONParseResult<(usize, String)> {
+        let (end_index, string) = self.parse_json_string_borrowed(from)?;
+        return Ok((end_index, string.into_owned()));
+    }
 
-    let mut parsing_exponent = false;
-    let mut found_decimal = false;
-    let mut found_exponent_sign = false;
+    /// Like `parse_json_string`, except a string without escapes is
+    /// borrowed from the input rather than copied
+    fn parse_json_string_borrowed(&self, from: usize) -> JSONParseResult<(usize, Cow<'a, str>)> {
+        let quote = self.bytes[from];
+        let mut i = from + 1;
+        let mut string_end_found = false;
+        let max_end = self.options.max_string_length.map(|max| from + 1 + max);
+
+        while let Some(byte) = self.bytes.get(i) {
+            if byte == &quote {
+                string_end_found = true;
+                break;
+            }
+
+            if matches!(max_end, Some(max_end) if i >= max_end) {
+                return Err(self.error(ParseJSONErrorKind::StringTooLong, from));
+            }
+
+            // Control characters have to be escaped to appear in a string
+            if byte < &b' ' {
+                return Err(self.error(ParseJSONErrorKind::UnescapedControlCharacter, i));
+            }
+
+            // Step over whatever follows a backslash, so a run of backslashes is
+            // consumed in pairs and a quote only ends the string when the run
+            // before it has even length
+            if byte == &b'\\' {
+                i += 1;
+            }
 
-    while let Some(ch) = chars.get(i) {
-        if ch.is_numeric() {
             i += 1;
-            continue;
         }
 
-        if ch == &'.' && !found_decimal && !parsing_exponent {
-            found_decimal = true;
-        } else if ch == &'e' || ch == &'E' {
-            parsing_exponent = true;
-        } else if ch == &'+' || ch == &'-' && parsing_exponent && !found_exponent_sign {
-            found_exponent_sign = true;
-        } else {
-            break;
+        if !string_end_found {
+            return Err(self.error(ParseJSONErrorKind::MissingEndQuote, from));
+        }
+        // An escape ending right at the limit steps past it onto the quote
+        if matches!(max_end, Some(max_end) if i > max_end) {
+            return Err(self.error(ParseJSONErrorKind::StringTooLong, from));
         }
 
-        i += 1;
+        return Ok((i, self.decode_json_string(from + 1, i)?));
     }
 
-    let parsed = chars[from..i]
-        .iter()
-        .collect::<String>()
-        .parse()
-        .map_err(|_| ParseJSONError("Invalid number"))?;
+    /// Given the range between a string's quotes, returns the
+    /// string with its escape sequences decoded, which is only copied out of
+    /// the input when it has any
+    fn decode_json_string(&self, from: usize, to: usize) -> JSONParseResult<Cow<'a, str>> {
+        let input: &'a str = self.input;
+        let mut output = String::new();
+        let mut run_start = from;
+        let mut i = from;
+
+        while i < to {
+            if self.bytes[i] != b'\\' {
+                i += 1;
+                continue;
+            }
+
+            // Text between escapes is copied over in one go
+            if run_start == from {
+                output.reserve(to - from);
+            }
+            output.push_str(&input[run_start..i]);
+
+            let invalid_escape = || self.error(ParseJSONErrorKind::InvalidEscape, i);
+            match self.bytes.get(i + 1).filter(|_| i + 1 < to) {
+                Some(&b'u') => {
+                    let (end_index, decoded) = self.decode_unicode_escape(i)?;
+                    output.push(decoded);
+                    i = end_index + 1;
+                }
+
+                Some(escaped) => {
+                    let decoded = match escaped {
+                        b'"' => '"',
+                        b'\'' if self.options.allow_single_quotes => '\'',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{08}',
+                        b'f' => '\u{0C}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        _ => return Err(invalid_escape()),
+                    };
+                    output.push(decoded);
+                    i += 2;
+                }
+
+                None => return Err(invalid_escape()),
+            }
+            run_start = i;
+        }
 
-    return Ok((i - 1, parsed));
-}
+        if run_start == from {
+            return Ok(Cow::Borrowed(&input[from..to]));
+        }
+        output.push_str(&input[run_start..to]);
+        return Ok(Cow::Owned(output));
+    }
 
-fn parse_json_literal(chars: &Vec<char>, from: usize, literal: &str) -> JSONParseResult<usize> {
-    let text = (from..from + literal.len())
-        .filter_map(|i| chars.get(i))
-        .collect::<String>();
-    let is_literal = text == literal;
-    return if is_literal {
-        Ok(from + literal.len() - 1)
-    } else {
-        Err(ParseJSONError("Unexpected literal"))
-    };
-}
+    /// Given the position of the '\' starting a '\uXXXX' escape,
+    /// returns the index of the last hex digit and the decoded char. A high
+    /// surrogate must be followed by an escaped low surrogate, which is
+    /// combined with it into a single char
+    fn decode_unicode_escape(&self, from: usize) -> JSONParseResult<(usize, char)> {
+        let invalid_escape = || self.error(ParseJSONErrorKind::InvalidUnicodeEscape, from);
+        let unpaired_surrogate = || self.error(ParseJSONErrorKind::UnpairedSurrogate, from);
+
+        let high = self
+            .parse_unicode_escape_digits(from + 2)
+            .ok_or_else(invalid_escape)?;
+
+        if !(0xD800..0xE000).contains(&high) {
+            let decoded = char::from_u32(high).ok_or_else(invalid_escape)?;
+            return Ok((from + 5, decoded));
+        }
 
-fn skip_whitespace(chars: &Vec<char>, from: usize) -> usize {
-    let mut i = from;
-    while matches!(chars.get(i), Some(ch) if ch.is_whitespace()) {
-        i += 1;
-    }
-    return i;
-}
+        let has_low_escape =
+            self.bytes.get(from + 6) == Some(&b'\\') && self.bytes.get(from + 7) == Some(&b'u');
+        if high >= 0xDC00 || !has_low_escape {
+            return Err(unpaired_surrogate());
+        }
 
-fn parse_json_array(chars: &Vec<char>, from: usize) -> JSONParseResult<(usize, Vec<JSONValue>)> {
-    let mut i = from + 1;
-
-    let mut output = vec![];
-    let mut array_should_end = false;
-    let mut is_ok_for_array_to_end = true;
-    let mut has_ended = false;
-
-    i = skip_whitespace(chars, i);
-    while let Some(ch) = chars.get(i) {
-        i = skip_whitespace(chars, i);
-
-        if ch == &']' && is_ok_for_array_to_end {
-            has_ended = true;
-            break;
-        } else if array_should_end {
-            return Err(ParseJSONError("Expected ']' to end array"));
-        } else if ch == &',' {
-            return Err(ParseJSONError("Unexpected comma"));
-        }
-
-        let (end_index, json_value) = parse_json_value(chars, i)?;
-        output.push(json_value);
-        i = end_index + 1;
-        i = skip_whitespace(chars, i);
-
-        // if the next char is a comma, we expect another item in this array
-        // so we should error if the array just ends
-        if chars.get(i) == Some(&',') {
-            i = skip_whitespace(chars, i + 1);
-            array_should_end = false;
-            is_ok_for_array_to_end = false;
-        } else {
-            array_should_end = true;
-            is_ok_for_array_to_end = true;
+        let low = self
+            .parse_unicode_escape_digits(from + 8)
+            .ok_or_else(invalid_escape)?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err(unpaired_surrogate());
         }
+
+        let code_point = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+        let decoded = char::from_u32(code_point).ok_or_else(invalid_escape)?;
+
+        return Ok((from + 11, decoded));
     }
 
-    if !has_ended {
-        return Err(ParseJSONError("Missing closing bracket ']' for array"));
+    /// Reads the four hex digits of a unicode escape starting at `from`
+    fn parse_unicode_escape_digits(&self, from: usize) -> Option<u32> {
+        let digits = self
+            .bytes
+            .get(from..from + 4)
+            .filter(|digits| digits.iter().all(u8::is_ascii_hexdigit))?;
+
+        return u32::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok();
     }
 
-    return Ok((i, output));
-}
+    /// Given the position of the first char of a number, returns
+    /// the index of its last char and the parsed number. Follows the JSON
+    /// grammar, so leading zeros and a '.' or exponent without digits after it
+    /// are rejected
+    fn parse_json_number(&self, from: usize) -> JSONParseResult<(usize, JSONValue)> {
+        let invalid_number = || self.error(ParseJSONErrorKind::InvalidNumber, from);
+        let is_digit = |i: usize| matches!(self.bytes.get(i), Some(byte) if byte.is_ascii_digit());
+        let skip_digits = |mut i: usize| {
+            while is_digit(i) {
+                i += 1;
+            }
+            i
+        };
+
+        let mut i = from;
+        if self.bytes.get(i) == Some(&b'-') {
+            i += 1;
+            if !is_digit(i) {
+                return Err(self.error(ParseJSONErrorKind::ExpectedDigitAfterMinus, i));
+            }
+        }
+
+        let is_hex_prefix = self.bytes.get(i) == Some(&b'0')
+            && matches!(self.bytes.get(i + 1), Some(&b'x') | Some(&b'X'));
+        if self.options.allow_hex_numbers && is_hex_prefix {
+            return self.parse_hex_number(from, i + 2);
+        }
+
+        // The integer part is either a lone zero, or digits that don't start
+        // with one
+        match self.bytes.get(i) {
+            Some(&b'0') if is_digit(i + 1) => return Err(invalid_number()),
+            Some(ch) if ch.is_ascii_digit() => i = skip_digits(i),
+            _ => return Err(invalid_number()),
+        }
+
+        let found_decimal = self.bytes.get(i) == Some(&b'.');
+        if found_decimal {
+            if !is_digit(i + 1) {
+                return Err(invalid_number());
+            }
+            i = skip_digits(i + 1);
+        }
 
-fn parse_json_object(
-    chars: &Vec<char>,
-    from: usize,
-) -> JSONParseResult<(usize, Vec<(String, JSONValue)>)> {
-    let mut i = from + 1;
+        let found_exponent = matches!(self.bytes.get(i), Some(&b'e') | Some(&b'E'));
+        if found_exponent {
+            i += 1;
+            if matches!(self.bytes.get(i), Some(&b'+') | Some(&b'-')) {
+                i += 1;
+            }
+            if !is_digit(i) {
+                return Err(invalid_number());
+            }
+            i = skip_digits(i);
+        }
+
+        // `i` is now just past the number, which is the end of the input when
+        // nothing follows it, so the last char is always at `i - 1`
+        let text = &self.input[from..i];
+        if self.options.preserve_number_text {
+            return Ok((i - 1, JSONValue::RawNumber(text.to_string())));
+        }
 
-    let mut output = vec![];
-    let mut object_should_end = false;
-    let mut is_ok_for_object_to_end = true;
-    let mut has_ended = false;
+        // Integers are kept exact rather than rounded to the nearest f64, unless
+        // they're too big for an i64. "-0" stays a float so its sign survives
+        if !found_decimal && !found_exponent && text != "-0" {
+            if let Ok(parsed) = text.parse() {
+                return Ok((i - 1, JSONValue::Integer(parsed)));
+            }
+        }
+
+        let parsed: f64 = text.parse().map_err(|_| invalid_number())?;
+        if self.options.reject_non_finite_results && !parsed.is_finite() {
+            return Err(self.error(ParseJSONErrorKind::NumberOutOfRange, from));
+        }
 
-    i = skip_whitespace(chars, i);
-    while let Some(ch) = chars.get(i) {
-        i = skip_whitespace(chars, i);
+        return Ok((i - 1, JSONValue::Number(parsed)));
+    }
 
-        if ch == &'}' && is_ok_for_object_to_end {
-            has_ended = true;
-            break;
-        } else if object_should_end {
-            return Err(ParseJSONError("Expected '}' to end object"));
-        } else if ch == &',' {
-            return Err(ParseJSONError("Unexpected comma"));
+    /// Given the position of a hex number's sign or `0x` prefix, and of the
+    /// digits after the prefix, returns the index of its last char and the
+    /// number. It's an `Integer` when it fits in an `i64`, and a `Number`
+    /// otherwise
+    fn parse_hex_number(
+        &self,
+        from: usize,
+        digits_start: usize,
+    ) -> JSONParseResult<(usize, JSONValue)> {
+        let mut i = digits_start;
+        while matches!(self.bytes.get(i), Some(byte) if byte.is_ascii_hexdigit()) {
+            i += 1;
+        }
+        if i == digits_start {
+            return Err(self.error(ParseJSONErrorKind::InvalidNumber, from));
         }
 
-        if chars.get(i) != Some(&'"') {
-            return Err(ParseJSONError(r#"Expected '"' for object key"#));
+        let digits = &self.input[digits_start..i];
+        let is_negative = self.bytes[from] == b'-';
+        let integer = u64::from_str_radix(digits, 16).ok().and_then(|magnitude| {
+            if is_negative {
+                0i64.checked_sub_unsigned(magnitude)
+                    .filter(|&integer| integer != 0)
+            } else {
+                i64::try_from(magnitude).ok()
+            }
+        });
+        if let Some(integer) = integer {
+            return Ok((i - 1, JSONValue::Integer(integer)));
         }
-        let (key_end_index, key_string) = parse_json_string(chars, i)?;
-        i = skip_whitespace(chars, key_end_index + 1);
 
-        if chars.get(i) != Some(&':') {
-            return Err(ParseJSONError("Expected ':' after object key"));
+        // Too big for an `i64`, or `-0x0`, which keeps its sign as a float
+        let magnitude = digits.chars().fold(0.0, |number, digit| {
+            return number * 16.0 + digit.to_digit(16).unwrap() as f64;
+        });
+        let number = if is_negative { -magnitude } else { magnitude };
+        return Ok((i - 1, JSONValue::Number(number)));
+    }
+
+    /// Given the position of the first char of `NaN`, `Infinity` or
+    /// `-Infinity`, returns the index of its last char and the number
+    fn parse_non_finite_number(&self, from: usize) -> JSONParseResult<(usize, JSONValue)> {
+        let (literal, number) = match self.bytes[from] {
+            b'N' => ("NaN", f64::NAN),
+            b'-' => ("-Infinity", f64::NEG_INFINITY),
+            _ => ("Infinity", f64::INFINITY),
+        };
+
+        let end_index = self.parse_json_literal(from, literal)?;
+        if !self.options.allow_non_finite {
+            return Err(self.error(ParseJSONErrorKind::NonFiniteNumber, from));
         }
 
-        i = skip_whitespace(chars, i + 1);
-        let (value_end_index, parsed_value) = parse_json_value(chars, i)?;
-        output.push((key_string, parsed_value));
-        i = skip_whitespace(chars, value_end_index + 1);
+        return Ok((end_index, JSONValue::Number(number)));
+    }
 
-        if chars.get(i) == Some(&',') {
-            i = skip_whitespace(chars, i + 1);
-            object_should_end = false;
-            is_ok_for_object_to_end = false;
+    /// Given the position a literal like `null` should start at, returns the
+    /// index of its last char. Whatever follows has to end the literal, so
+    /// `nullable` isn't read as `null` followed by junk
+    fn parse_json_literal(&self, from: usize, literal: &str) -> JSONParseResult<usize> {
+        let end = from + literal.len();
+        let is_literal = self.bytes.get(from..end) == Some(literal.as_bytes());
+        return if is_literal && self.is_delimiter(end) {
+            Ok(from + literal.len() - 1)
         } else {
-            object_should_end = true;
-            is_ok_for_object_to_end = true;
+            Err(self.error(ParseJSONErrorKind::UnexpectedLiteral, from))
+        };
+    }
+
+    /// Whether the char at `position` can come straight after a value,
+    /// which includes the end of the input
+    fn is_delimiter(&self, position: usize) -> bool {
+        return match self.bytes.get(position) {
+            None | Some(&b',') | Some(&b']') | Some(&b'}') => true,
+            Some(&b'/') => self.options.allow_comments,
+            Some(_) => self.skip_whitespace(position) != position,
+        };
+    }
+
+    /// Skips spaces, tabs, newlines and carriage returns, and comments too
+    /// when they're allowed
+    fn skip_whitespace(&self, from: usize) -> usize {
+        let mut i = from;
+        loop {
+            match self.bytes.get(i) {
+                Some(&b'/') if self.options.allow_comments => match self.skip_comment(i) {
+                    Some(comment_end) => i = comment_end,
+                    None => break,
+                },
+
+                // Only the four whitespace chars JSON allows, so other
+                // Unicode whitespace like U+00A0 is an error
+                Some(b' ' | b'\t' | b'\n' | b'\r') => i += 1,
+
+                _ => break,
+            }
         }
+        return i;
     }
 
-    if !has_ended {
-        return Err(ParseJSONError("Missing closing brace '}' for object"));
+    /// Given the position of a '/', returns the position just after the
+    /// comment it starts, or `None` if it doesn't start one. A line comment
+    /// can run to the end of the input, but a block comment that's never
+    /// closed isn't a comment at all
+    fn skip_comment(&self, from: usize) -> Option<usize> {
+        return match self.bytes.get(from + 1) {
+            Some(&b'/') => Some(
+                self.input[from + 2..]
+                    .find('\n')
+                    .map_or(self.input.len(), |offset| from + 2 + offset + 1),
+            ),
+            Some(&b'*') => self.input[from + 2..]
+                .find("*/")
+                .map(|offset| from + 2 + offset + 2),
+            _ => None,
+        };
     }
 
-    return Ok((i, output));
-}
+    /// Whether a block comment that's never closed starts at `position`,
+    /// when comments are allowed
+    fn is_unclosed_comment(&self, position: usize) -> bool {
+        return self.options.allow_comments
+            && self.bytes.get(position..position + 2) == Some(b"/*")
+            && self.skip_comment(position).is_none();
+    }
+
+    /// Parses the array starting at `from`, returning the index of its ']'
+    /// and its items
+    #[cfg(test)]
+    fn parse_json_array(&mut self, from: usize) -> JSONParseResult<(usize, Vec<B::Value>)> {
+        return match self.parse_container(Frame::new(from, Contents::Array(vec![])))? {
+            (end_index, Contents::Array(items)) => Ok((end_index, items)),
+            (_, Contents::Object { .. }) => unreachable!("an array frame ends as an array"),
+        };
+    }
+
+    /// Parses the object starting at `from`, returning the index of its '}'
+    /// and its members
+    #[cfg(test)]
+    fn parse_json_object(&mut self, from: usize) -> JSONParseResult<(usize, Members<'a, B>)> {
+        let contents = Contents::Object {
+            members: vec![],
+            seen_keys: HashMap::new(),
+            key: None,
+        };
+        return match self.parse_container(Frame::new(from, contents))? {
+            (end_index, Contents::Object { members, .. }) => Ok((end_index, members)),
+            (_, Contents::Array(_)) => unreachable!("an object frame ends as an object"),
+        };
+    }
 
-pub fn parse_json_value(chars: &Vec<char>, from: usize) -> JSONParseResult<(usize, JSONValue)> {
-    let mut i = from;
+    /// Parses the array or object `frame` was started for, along with
+    /// everything nested in it. Nested arrays and objects go on a stack of
+    /// their own rather than being parsed by recursing, so how deep the input
+    /// goes is only limited by memory. Returns the index of the outermost
+    /// container's last char and what it holds
+    fn parse_container(
+        &mut self,
+        frame: Frame<'a, B>,
+    ) -> JSONParseResult<(usize, Contents<'a, B>)> {
+        let mut stack = vec![frame];
+        // What the innermost container's latest item or member value came to,
+        // or `None` when the container has only just been started
+        let mut child = None;
+
+        while let Some(frame) = stack.last_mut() {
+            let value_start = match self.resume(frame, child.take())? {
+                Resume::Value(value_start) => value_start,
+                Resume::End(end_index) => {
+                    let frame = stack.pop().unwrap();
+                    if stack.is_empty() {
+                        return Ok((end_index, frame.contents));
+                    }
+
+                    self.depth -= 1;
+                    let span = Span {
+                        start: frame.start,
+                        end: end_index + 1,
+                    };
+                    let value = match frame.contents {
+                        Contents::Array(items) => B::array(items, span),
+                        Contents::Object { members, .. } => {
+                            self.open_objects -= 1;
+                            B::object(members, span)
+                        }
+                    };
+                    child = Some(Ok((self.skip_whitespace(end_index), value)));
+                    continue;
+                }
+            };
+
+            child = match self.start_value(value_start) {
+                Ok(Started::Scalar(end_index, value)) => Some(Ok((end_index, value))),
+                Ok(Started::Container(frame)) => {
+                    stack.push(frame);
+                    None
+                }
+                Err(error) => Some(Err(error)),
+            };
+        }
+
+        unreachable!("the outermost container returns when it ends");
+    }
+
+    /// Carries on parsing the container `frame` is for, given what its latest
+    /// item or member value came to, until it needs another value parsed or
+    /// it ends
+    fn resume(
+        &mut self,
+        frame: &mut Frame<'a, B>,
+        child: Option<JSONParseResult<(usize, B::Value)>>,
+    ) -> JSONParseResult<Resume> {
+        let is_object = matches!(frame.contents, Contents::Object { .. });
+        let (end_byte, other_end_byte, expected) = if is_object {
+            (b'}', b']', '}')
+        } else {
+            (b']', b'}', ']')
+        };
+
+        let mut i = match child {
+            None => self.skip_whitespace(frame.start + 1),
+            Some(Ok((value_end_index, value))) => {
+                self.add_item(frame, value)?;
+                self.expect_separator(frame, self.skip_whitespace(value_end_index + 1))
+            }
+            Some(Err(error)) => {
+                self.recover(error)?;
+                self.expect_separator(frame, self.skip_junk(frame.item_start))
+            }
+        };
+
+        while let Some(&byte) = self.bytes.get(i) {
+            if byte == end_byte && frame.is_ok_to_end {
+                return Ok(Resume::End(i));
+            } else if self.recovering && byte == other_end_byte {
+                // The end of an enclosing container of the other kind when
+                // there is one, so this one is closed just before it, and
+                // otherwise a stray
+                self.recover(self.unexpected_char_error(expected, i))?;
+                let is_enclosed = if is_object {
+                    self.depth > self.open_objects
+                } else {
+                    self.open_objects > 0
+                };
+                if is_enclosed {
+                    return Ok(Resume::End(i - 1));
+                }
+                i = self.skip_whitespace(i + 1);
+            } else if frame.should_end {
+                self.recover(self.unexpected_char_error(expected, i))?;
+                i = self.skip_junk(i);
+            } else if byte == b',' {
+                self.recover(self.error(ParseJSONErrorKind::UnexpectedComma, i))?;
+                i = self.skip_whitespace(i + 1);
+                continue;
+            } else {
+                frame.item_start = i;
+                let Contents::Object { key, .. } = &mut frame.contents else {
+                    return Ok(Resume::Value(i));
+                };
+                match self.parse_json_member_key(i) {
+                    Ok((value_start, key_string, key_span)) => {
+                        *key = Some((key_string, key_span));
+                        return Ok(Resume::Value(value_start));
+                    }
+                    Err(error) => {
+                        self.recover(error)?;
+                        i = self.skip_junk(i);
+                    }
+                }
+            }
+
+            i = self.expect_separator(frame, i);
+        }
 
-    i = skip_whitespace(chars, i);
+        // Running out of input straight after an item means only the end is
+        // missing, otherwise another item was still expected
+        let kind = match (frame.should_end, is_object) {
+            (true, true) => ParseJSONErrorKind::MissingObjectEnd,
+            (true, false) => ParseJSONErrorKind::MissingArrayEnd,
+            (false, _) => ParseJSONErrorKind::UnexpectedEof,
+        };
+        self.recover_at_end(self.error(kind, i))?;
+        return Ok(Resume::End(self.bytes.len() - 1));
+    }
 
-    let ch = chars.get(i);
+    /// Given the position just after an item or member, notes whether the
+    /// container can end or another item is expected, and returns where to
+    /// carry on from
+    fn expect_separator(&self, frame: &mut Frame<'a, B>, from: usize) -> usize {
+        // if the next char is a comma, we expect another item in this
+        // container so we should error if it just ends, unless trailing
+        // commas are allowed
+        if self.bytes.get(from) == Some(&b',') {
+            frame.should_end = false;
+            frame.is_ok_to_end = self.options.allow_trailing_commas;
+            return self.skip_whitespace(from + 1);
+        }
+        frame.should_end = true;
+        frame.is_ok_to_end = true;
+        return from;
+    }
 
-    let (value_end_index, json_value) = match ch {
-        // Strings
-        Some(&'"') => {
-            let (end_index, parsed_string) = parse_json_string(&chars, i)?;
-            (end_index, JSONValue::String(parsed_string))
+    /// Adds a parsed item to an array, or a member's value to an object
+    /// along with its key, as the duplicate key policy says
+    fn add_item(&mut self, frame: &mut Frame<'a, B>, value: B::Value) -> JSONParseResult<()> {
+        let (members, seen_keys, key) = match &mut frame.contents {
+            Contents::Array(items) => {
+                items.push(value);
+                return Ok(());
+            }
+            Contents::Object {
+                members,
+                seen_keys,
+                key,
+            } => (members, seen_keys, key),
+        };
+        let (key_string, key_span) = key
+            .take()
+            .expect("a member's key is parsed before its value");
+
+        let is_kept = self.options.duplicate_keys == DuplicateKeyPolicy::Keep
+            || !seen_keys.contains_key(&key_string);
+        let is_full = matches!(self.options.max_object_members, Some(max_members) if members.len() >= max_members);
+        if is_kept && is_full {
+            let error = self.error(ParseJSONErrorKind::MaxObjectMembersExceeded, key_span.start);
+            return self.recover(error);
         }
 
-        // null
-        Some(&'n') => {
-            let end_index = parse_json_literal(&chars, i, "null")?;
-            (end_index, JSONValue::Null)
+        if self.options.duplicate_keys == DuplicateKeyPolicy::Keep {
+            members.push((B::key(key_string, key_span), value));
+        } else if let Some(&existing_index) = seen_keys.get(&key_string) {
+            match self.options.duplicate_keys {
+                DuplicateKeyPolicy::Last => members[existing_index].1 = value,
+                DuplicateKeyPolicy::Error => {
+                    self.recover(self.error(
+                        ParseJSONErrorKind::DuplicateKey {
+                            key: key_string.into_owned(),
+                        },
+                        key_span.start,
+                    ))?;
+                }
+                DuplicateKeyPolicy::First | DuplicateKeyPolicy::Keep => {}
+            }
+        } else {
+            seen_keys.insert(key_string.clone(), members.len());
+            members.push((B::key(key_string, key_span), value));
         }
+        return Ok(());
+    }
 
-        // booleans
-        Some(&'t') => {
-            let end_index = parse_json_literal(&chars, i, "true")?;
-            (end_index, JSONValue::True)
+    /// Given the position of a member's key, returns where its value starts,
+    /// along with the key and the key's span
+    fn parse_json_member_key(&self, from: usize) -> JSONParseResult<(usize, Cow<'a, str>, Span)> {
+        let is_identifier_char = |i: usize| matches!(self.bytes.get(i), Some(byte) if byte.is_ascii_alphanumeric() || byte == &b'_' || byte == &b'$');
+        let is_identifier_start = self.options.allow_unquoted_keys
+            && is_identifier_char(from)
+            && !self.bytes[from].is_ascii_digit();
+
+        let input: &'a str = self.input;
+        let (key_end_index, key_string) = if self.is_quote(from) {
+            self.parse_json_string_borrowed(from)?
+        } else if is_identifier_start {
+            let mut i = from;
+            while is_identifier_char(i + 1) {
+                i += 1;
+            }
+            (i, Cow::Borrowed(&input[from..=i]))
+        } else {
+            return Err(self.unexpected_char_error('"', from));
+        };
+        let key_span = Span {
+            start: from,
+            end: key_end_index + 1,
+        };
+        let i = self.skip_whitespace(key_end_index + 1);
+
+        if self.bytes.get(i) != Some(&b':') {
+            return Err(self.unexpected_char_error(':', i));
         }
-        Some(&'f') => {
-            let end_index = parse_json_literal(&chars, i, "false")?;
-            (end_index, JSONValue::False)
+
+        return Ok((self.skip_whitespace(i + 1), key_string, key_span));
+    }
+
+    /// Given the position of a value, or whitespace before it, parses it if
+    /// it's a scalar, or starts a frame for it if it's an array or object
+    fn start_value(&mut self, from: usize) -> JSONParseResult<Started<'a, B>> {
+        let i = self.skip_whitespace(from);
+
+        self.count_element(i)?;
+
+        return match self.bytes.get(i) {
+            Some(&b'[') => {
+                self.enter_container(i)?;
+                Ok(Started::Container(Frame::new(i, Contents::Array(vec![]))))
+            }
+
+            Some(&b'{') => {
+                self.enter_container(i)?;
+                self.open_objects += 1;
+                let contents = Contents::Object {
+                    members: vec![],
+                    seen_keys: HashMap::new(),
+                    key: None,
+                };
+                Ok(Started::Container(Frame::new(i, contents)))
+            }
+
+            // Interned strings are built as scalars, since they're shared
+            // rather than borrowed
+            _ if self.is_quote(i) && !self.options.intern_strings => {
+                let (end_index, string) = self.parse_json_string_borrowed(i)?;
+                let span = Span {
+                    start: i,
+                    end: end_index + 1,
+                };
+                Ok(Started::Scalar(
+                    self.skip_whitespace(end_index),
+                    B::string(string, span),
+                ))
+            }
+
+            _ => {
+                let (end_index, mut scalar) = self.parse_json_scalar(i)?;
+                if let JSONValue::String(string) = &mut scalar {
+                    if self.options.intern_strings {
+                        *string = JSONString::Shared(string::intern(&mut self.interned, string));
+                    }
+                }
+                let span = Span {
+                    start: i,
+                    end: end_index + 1,
+                };
+                Ok(Started::Scalar(
+                    self.skip_whitespace(end_index),
+                    B::scalar(scalar, span),
+                ))
+            }
+        };
+    }
+
+    fn parse_json_value(&mut self, from: usize) -> JSONParseResult<(usize, B::Value)> {
+        let frame = match self.start_value(from)? {
+            Started::Scalar(end_index, value) => return Ok((end_index, value)),
+            Started::Container(frame) => frame,
+        };
+
+        let start = frame.start;
+        let (end_index, contents) = self.parse_container(frame)?;
+        self.depth -= 1;
+        let span = Span {
+            start,
+            end: end_index + 1,
+        };
+        let json_value = match contents {
+            Contents::Array(items) => B::array(items, span),
+            Contents::Object { members, .. } => {
+                self.open_objects -= 1;
+                B::object(members, span)
+            }
+        };
+
+        return Ok((self.skip_whitespace(end_index), json_value));
+    }
+
+    /// Where the input starts once a leading UTF-8 byte order mark, which
+    /// some editors write, is skipped. One anywhere else is an error
+    fn content_start(&self) -> usize {
+        return if self.input.starts_with('\u{FEFF}') {
+            '\u{FEFF}'.len_utf8()
+        } else {
+            0
+        };
+    }
+
+    /// Parses the whole input as a single value
+    fn parse_document(&mut self) -> JSONParseResult<B::Value> {
+        let value_start = self.skip_whitespace(self.content_start());
+        let is_container = matches!(self.bytes.get(value_start), Some(&b'[') | Some(&b'{'));
+        if self.options.require_container && !is_container && value_start < self.bytes.len() {
+            return Err(self.error(ParseJSONErrorKind::ExpectedContainer, value_start));
         }
 
-        // numbers
-        Some(ch) if ch.is_ascii_digit() || ch == &'-' => {
-            let (end_index, parsed_number) = parse_json_number(&chars, i)?;
-            (end_index, JSONValue::Number(parsed_number))
+        if value_start == self.bytes.len() {
+            return Err(self.error(ParseJSONErrorKind::EmptyInput, value_start));
         }
 
-        Some(&'[') => {
-            let (end_index, parsed_array) = parse_json_array(chars, i)?;
-            (end_index, JSONValue::Array(parsed_array))
+        let (end_index, json_value) = self.parse_json_value(value_start)?;
+
+        // Only whitespace may follow the top-level value
+        let trailing_index = self.skip_whitespace(end_index + 1);
+        if trailing_index != self.bytes.len() {
+            self.recover(self.error(ParseJSONErrorKind::TrailingCharacters, trailing_index))?;
         }
 
-        Some(&'{') => {
-            let (end_index, parsed_object) = parse_json_object(chars, i)?;
-            (end_index, JSONValue::Object(parsed_object))
+        return Ok(json_value);
+    }
+
+    /// Given the position of the first char of anything but an array or
+    /// object, returns the index of its last char and the value
+    fn parse_json_scalar(&self, from: usize) -> JSONParseResult<(usize, JSONValue)> {
+        let i = from;
+
+        let scalar = match self.bytes.get(i) {
+            // Strings
+            _ if self.is_quote(i) => {
+                let (end_index, parsed_string) = self.parse_json_string(i)?;
+                (end_index, JSONValue::String(parsed_string.into()))
+            }
+
+            // null
+            Some(&b'n') => {
+                let end_index = self.parse_json_literal(i, "null")?;
+                (end_index, JSONValue::Null)
+            }
+
+            // booleans
+            Some(&b't') => {
+                let end_index = self.parse_json_literal(i, "true")?;
+                (end_index, JSONValue::True)
+            }
+            Some(&b'f') => {
+                let end_index = self.parse_json_literal(i, "false")?;
+                (end_index, JSONValue::False)
+            }
+
+            // NaN and infinities, which have to be checked before other numbers
+            Some(&b'N') | Some(&b'I') => self.parse_non_finite_number(i)?,
+            Some(&b'-') if self.bytes.get(i + 1) == Some(&b'I') => {
+                self.parse_non_finite_number(i)?
+            }
+
+            // numbers, including malformed ones like ".5" so they get a number
+            // error rather than a generic one
+            Some(byte) if byte.is_ascii_digit() || byte == &b'-' || byte == &b'.' => {
+                self.parse_json_number(i)?
+            }
+
+            None => return Err(self.error(ParseJSONErrorKind::UnexpectedEof, i)),
+            _ => return Err(self.error(ParseJSONErrorKind::NoValue, i)),
+        };
+
+        return Ok(scalar);
+    }
+}
+
+/// Given the chars, and the position to start from, parses a single value
+/// with the default options, returning the index of its last char. Error
+/// positions are char indices too. Every call copies all of `chars` into a
+/// string to parse, so parsing many values out of one long input this way
+/// is slow, and `parse_json` on the string itself is better
+pub fn parse_json_value(chars: &[char], from: usize) -> JSONParseResult<(usize, JSONValue)> {
+    let string = chars.iter().collect::<String>();
+    // Starting past the end fails like starting at it does
+    let byte_from = match chars.get(..from) {
+        Some(prefix) => prefix.iter().map(|ch| ch.len_utf8()).sum(),
+        None => string.len(),
+    };
+    let char_index = |byte_index: usize| -> usize {
+        return from.min(chars.len()) + string[byte_from..byte_index].chars().count();
+    };
+
+    return match Parser::<BuildJSONValue>::new(&string, ParseOptions::default())
+        .parse_json_value(byte_from)
+    {
+        Ok((byte_end_index, json_value)) => Ok((char_index(byte_end_index), json_value)),
+        Err(error) => {
+            let position = char_index(error.position().clamp(byte_from, string.len()));
+            Err(error.at_position(position))
         }
+    };
+}
+
+pub fn parse_json(string: &str) -> JSONParseResult<JSONValue> {
+    return parse_json_with_options(string, &ParseOptions::default());
+}
+
+pub fn parse_json_with_options(string: &str, options: &ParseOptions) -> JSONParseResult<JSONValue> {
+    return parse_document::<BuildJSONValue>(string, options);
+}
+
+/// Parses the whole of `string` as a single value, built by `B`
+pub(crate) fn parse_document<'a, B: Build<'a>>(
+    string: &'a str,
+    options: &ParseOptions,
+) -> JSONParseResult<B::Value> {
+    return Parser::<B>::new(string, options.clone()).parse_document();
+}
 
-        _ => return Err(ParseJSONError("No JSON value found")),
+/// Parses as much of `string` as possible with the default options, for
+/// tools like editors that have to cope with unfinished or broken input.
+/// Errors are collected rather than ending the parse: arrays and objects
+/// left open at the end of the input are closed, anything that can't be
+/// parsed inside them is skipped up to the next `,`, `]` or `}`, and a `]`
+/// or `}` that doesn't match is taken to close an enclosing array or object
+/// of its kind when there is one, and skipped otherwise. Gives the
+/// value built from everything that could be parsed, unless not even its
+/// start could be, and every error found along the way
+pub fn parse_json_lenient(string: &str) -> (Option<JSONValue>, Vec<ParseJSONError>) {
+    let mut parser = Parser::<BuildJSONValue>::new(string, ParseOptions::default());
+    parser.recovering = true;
+
+    let value = match parser.parse_document() {
+        Ok(value) => Some(value),
+        Err(error) => {
+            parser.errors.push(error);
+            None
+        }
     };
+    return (value, parser.errors);
+}
+
+/// The byte offset and message of the first syntax error in `string`, when
+/// it isn't valid JSON by the default options, for things like an editor
+/// marking where a document goes wrong. The message is the error kind's,
+/// without the line and column `ParseJSONError` displays
+pub fn find_first_error(string: &str) -> Option<(usize, String)> {
+    return parse_json(string)
+        .err()
+        .map(|error| (error.position(), error.kind().to_string()));
+}
 
-    i = skip_whitespace(chars, value_end_index);
+/// Decodes a JSON string literal, quotes included, into the string it
+/// stands for, failing as parsing would when it isn't one. Nothing but the
+/// literal may be in `literal`, not even whitespace
+pub fn unescape_json_string(literal: &str) -> JSONParseResult<String> {
+    let parser = Parser::<BuildJSONValue>::new(literal, ParseOptions::default());
+    if !literal.starts_with('"') {
+        return Err(parser.unexpected_char_error('"', 0));
+    }
 
-    return Ok((i, json_value));
+    let (end_index, string) = parser.parse_json_string(0)?;
+    if end_index + 1 != literal.len() {
+        return Err(parser.error(ParseJSONErrorKind::TrailingCharacters, end_index + 1));
+    }
+    return Ok(string);
 }
 
-pub fn parse_json(string: &str) -> JSONParseResult<JSONValue> {
-    let chars = string.chars().collect::<Vec<char>>();
+/// Parses a lone JSON number, like a field that might be numeric, failing
+/// as parsing would when it isn't one. Nothing but the number may be in
+/// `number`, not even whitespace, and `NaN` and infinities aren't numbers.
+/// Integers too big to be exact as an `f64` are rounded
+pub fn parse_number(number: &str) -> JSONParseResult<f64> {
+    let parser = Parser::<BuildJSONValue>::new(number, ParseOptions::default());
+
+    let (end_index, value) = parser.parse_json_number(0)?;
+    if end_index + 1 != number.len() {
+        return Err(parser.error(ParseJSONErrorKind::TrailingCharacters, end_index + 1));
+    }
+    return Ok(value
+        .as_f64()
+        .expect("a parsed number is an Integer or Number"));
+}
+
+/// Parses the value at the start of `string` with the default options,
+/// skipping whitespace before it, and returns it with the byte offset just
+/// past it. Whatever follows the value isn't looked at, so it doesn't have
+/// to be JSON, though `true`, `false` and `null` still can't run straight
+/// into other letters
+pub fn parse_json_prefix(string: &str) -> JSONParseResult<(JSONValue, usize)> {
+    let mut parser = Parser::<BuildJSONValue>::new(string, ParseOptions::default());
+
+    let value_start = parser.skip_whitespace(parser.content_start());
+    if value_start == string.len() {
+        return Err(parser.error(ParseJSONErrorKind::EmptyInput, value_start));
+    }
+
+    let (end_index, value) = parser.parse_json_value(value_start)?;
+    return Ok((value, end_index + 1));
+}
+
+/// Parses a stream of values one after another, like newline-delimited
+/// JSON, with the default options. Whitespace, including newlines, may go
+/// between values, and has to follow a number or literal that another
+/// value comes after, so `1"a"` is an error. Empty or all-whitespace input
+/// gives no values
+pub fn parse_json_many(string: &str) -> JSONParseResult<Vec<JSONValue>> {
+    let mut parser = Parser::<BuildJSONValue>::new(string, ParseOptions::default());
+    let mut values = vec![];
+
+    let mut i = parser.skip_whitespace(parser.content_start());
+    while i < string.len() {
+        let (end_index, value) = parser.parse_json_value(i)?;
+        // Literals already check what follows them, but a number stops at
+        // anything that can't go on it
+        let is_number = matches!(
+            value,
+            JSONValue::Integer(_) | JSONValue::Number(_) | JSONValue::RawNumber(_)
+        );
+        if is_number && !parser.is_delimiter(end_index + 1) {
+            return Err(parser.error(ParseJSONErrorKind::InvalidNumber, i));
+        }
+        values.push(value);
+        i = parser.skip_whitespace(end_index + 1);
+    }
+
+    return Ok(values);
+}
+
+/// Parses `bytes` as a single JSON document, which has to be UTF-8 unless
+/// `on_invalid_utf8` says to repair it. A string holding a bad byte then
+/// holds U+FFFD in its place
+pub fn parse_json_bytes(bytes: &[u8], options: &ParseOptions) -> JSONParseResult<JSONValue> {
+    let string = match (std::str::from_utf8(bytes), options.on_invalid_utf8) {
+        (Ok(string), _) => Cow::Borrowed(string),
+        (Err(_), InvalidUtf8::Replace) => String::from_utf8_lossy(bytes),
+        (Err(err), InvalidUtf8::Error) => {
+            let kind = ParseJSONErrorKind::InvalidUtf8;
+            return Err(ParseJSONError::new(kind, bytes, err.valid_up_to()));
+        }
+    };
+
+    return parse_json_with_options(&string, options);
+}
+
+/// Reads everything from `reader` and parses it as a single JSON document.
+/// The input is read in chunks and decoded as UTF-8 as it comes in, so only
+/// the decoded text is kept rather than the raw bytes as well, though the
+/// parser still needs all of it before it starts. Failing to read, or
+/// reading bytes that aren't UTF-8, gives an `Io` or `InvalidUtf8` error at
+/// the byte offset reached
+pub fn parse_json_reader<R: Read>(mut reader: R) -> JSONParseResult<JSONValue> {
+    let mut string = String::new();
+    let mut buffer = [0; 8192];
+    // The start of a char split across reads, held back until the rest of
+    // it arrives
+    let mut pending = vec![];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => {
+                let kind = ParseJSONErrorKind::Io {
+                    kind: err.kind(),
+                    message: err.to_string(),
+                };
+                return Err(ParseJSONError::new(kind, string.as_bytes(), string.len()));
+            }
+        };
+        pending.extend_from_slice(&buffer[..read]);
+
+        let valid_up_to = match std::str::from_utf8(&pending) {
+            Ok(_) => pending.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(err) => {
+                string.push_str(&String::from_utf8_lossy(&pending[..err.valid_up_to()]));
+                let kind = ParseJSONErrorKind::InvalidUtf8;
+                return Err(ParseJSONError::new(kind, string.as_bytes(), string.len()));
+            }
+        };
+        string.push_str(&String::from_utf8_lossy(&pending[..valid_up_to]));
+        pending.drain(..valid_up_to);
+    }
+
+    if !pending.is_empty() {
+        let kind = ParseJSONErrorKind::InvalidUtf8;
+        return Err(ParseJSONError::new(kind, string.as_bytes(), string.len()));
+    }
+    return parse_json(&string);
+}
 
-    let (_end_index, json_value) = parse_json_value(&chars, 0)?;
+impl FromStr for JSONValue {
+    type Err = ParseJSONError;
 
-    return Ok(json_value);
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        return parse_json(string);
+    }
 }
 
 #[cfg(test)]
@@ -268,10 +1444,18 @@ mod tests {
     use super::*;
     use JSONValue::*;
 
+    fn chars(input: &str) -> Vec<char> {
+        return input.chars().collect();
+    }
+
+    fn parser(input: &str) -> Parser<'_> {
+        return Parser::new(input, ParseOptions::default());
+    }
+
     #[test]
     fn parse_json_string_simple_string() {
         assert_eq!(
-            parse_json_string(&r#"   "hello, world!""#.chars().collect(), 3),
+            parser(r#"   "hello, world!""#).parse_json_string(3),
             Ok((17, "hello, world!".to_string()))
         );
     }
@@ -279,188 +1463,616 @@ mod tests {
     #[test]
     fn parse_json_string_double_escape_before_closing_quotes() {
         assert_eq!(
-            parse_json_string(&r#""{{a}}\\""#.chars().collect(), 0),
-            Ok((8, r#"{{a}}\\"#.to_string()))
+            parser(r#""{{a}}\\""#).parse_json_string(0),
+            Ok((8, r#"{{a}}\"#.to_string()))
         )
     }
 
     #[test]
     fn parse_json_string_with_escapes() {
         assert_eq!(
-            parse_json_string(&r#""hello\", world!""#.chars().collect(), 0),
-            Ok((16, r#"hello\", world!"#.to_string()))
+            parser(r#""hello\", world!""#).parse_json_string(0),
+            Ok((16, r#"hello", world!"#.to_string()))
         );
     }
 
     #[test]
-    fn parse_json_string_incomplete_string_err() {
+    fn parse_json_string_escaped_backslash_before_end() {
         assert_eq!(
-            parse_json_string(&r#""hello, world!"#.chars().collect(), 0),
-            Err(ParseJSONError("Missing end quotes for string"))
+            parser(r#""a\\""#).parse_json_string(0),
+            Ok((4, r#"a\"#.to_string()))
         );
     }
 
     #[test]
-    #[ignore]
-    fn parse_json_string_with_unicode() {
+    fn parse_json_string_escaped_backslash_then_escaped_quote() {
         assert_eq!(
-            parse_json_string(&r#""\u0928""#.chars().collect(), 0),
-            Ok((7, "न".to_string()))
-        )
+            parser(r#""a\\\"""#).parse_json_string(0),
+            Ok((6, r#"a\""#.to_string()))
+        );
     }
 
     #[test]
-    fn parse_json_just_string() {
+    fn parse_json_string_with_standard_escapes() {
         assert_eq!(
-            parse_json(r#""hello, world!""#),
-            Ok(JSONValue::String("hello, world!".to_string()))
+            parser(r#""\"\\\/\b\f\n\r\t""#).parse_json_string(0),
+            Ok((17, "\"\\/\u{08}\u{0C}\n\r\t".to_string()))
         );
     }
 
     #[test]
-    fn parse_json_number_1() {
+    fn parse_json_string_escapes_around_text() {
         assert_eq!(
-            parse_json_number(&r#"-1.2e+3"#.chars().collect(), 0),
-            Ok((6, -1200f64))
+            parser(r#""line\nbreak\ttab""#).parse_json_string(0),
+            Ok((17, "line\nbreak\ttab".to_string()))
         );
     }
 
     #[test]
-    fn parse_json_number_2() {
+    fn parse_json_string_escapes_with_unicode() {
         assert_eq!(
-            parse_json_number(&r#"-1.2E-3,"#.chars().collect(), 0),
-            Ok((6, -0.0012f64))
+            parser(r#""\"\u0041\"\n""#).parse_json_string(0),
+            Ok((13, "\"A\"\n".to_string()))
         );
     }
 
     #[test]
-    fn parse_json_just_number() {
-        assert_eq!(parse_json(&r#"-1.2e+3"#), Ok(JSONValue::Number(-1200f64)));
+    fn parse_json_string_invalid_escape_err() {
+        assert_eq!(
+            parser(r#""a\x""#).parse_json_string(0).unwrap_err().kind(),
+            &ParseJSONErrorKind::InvalidEscape
+        );
     }
 
     #[test]
-    fn parse_json_null() {
-        assert_eq!(parse_json("null"), Ok(JSONValue::Null));
+    fn parse_json_string_incomplete_string_err() {
+        assert_eq!(
+            parser(r#""hello, world!"#)
+                .parse_json_string(0)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::MissingEndQuote
+        );
     }
 
     #[test]
-    fn parse_json_true() {
-        assert_eq!(parse_json("true"), Ok(JSONValue::True));
+    fn parse_json_string_raw_tab_err() {
+        let err = parser("\"a\tb\"").parse_json_string(0).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::UnescapedControlCharacter);
+        assert_eq!(err.position(), 2);
     }
 
     #[test]
-    fn parse_json_false() {
-        assert_eq!(parse_json("false"), Ok(JSONValue::False));
+    fn parse_json_string_raw_newline_err() {
+        assert_eq!(
+            parse_json("[\"line\nbreak\"]").unwrap_err().kind(),
+            &ParseJSONErrorKind::UnescapedControlCharacter
+        );
     }
 
     #[test]
-    fn parse_json_array_empty_array() {
+    fn parse_json_string_with_unicode() {
         assert_eq!(
-            parse_json_array(&"[]".chars().collect(), 0),
-            Ok((1, vec![]))
+            parser(r#""\u0928""#).parse_json_string(0),
+            Ok((7, "न".to_string()))
         )
     }
 
     #[test]
-    fn parse_json_array_numbers_array() {
+    fn parse_json_string_with_surrogate_pair() {
         assert_eq!(
-            parse_json_array(&"[ 1 , 2 , 3 ]".chars().collect(), 0),
-            Ok((
-                12,
-                vec![
-                    JSONValue::Number(1f64),
-                    JSONValue::Number(2f64),
-                    JSONValue::Number(3f64),
-                ]
-            ))
+            parser(r#""hi \uD83D\uDE00!""#).parse_json_string(0),
+            Ok((17, "hi 😀!".to_string()))
         )
     }
 
     #[test]
-    fn parse_json_array_trailing_comma() {
+    fn parse_json_string_lone_high_surrogate_err() {
         assert_eq!(
-            parse_json_array(&"[1, 2,]".chars().collect(), 0),
-            Err(ParseJSONError("No JSON value found"))
+            parser(r#""\uD83D""#)
+                .parse_json_string(0)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::UnpairedSurrogate
         )
     }
 
     #[test]
-    fn parse_json_array_double_comma() {
+    fn parse_json_string_high_surrogate_without_low_err() {
         assert_eq!(
-            parse_json_array(&"[1, 2,,]".chars().collect(), 0),
-            Err(ParseJSONError("Unexpected comma"))
+            parser(r#""\uD83DA""#)
+                .parse_json_string(0)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::UnpairedSurrogate
         )
     }
 
     #[test]
-    fn parse_json_array_missing_comma() {
+    fn parse_json_string_lone_low_surrogate_err() {
         assert_eq!(
-            parse_json_array(&"[1, 2  3]".chars().collect(), 0),
-            Err(ParseJSONError("Expected ']' to end array"))
+            parser(r#""\uDE00""#)
+                .parse_json_string(0)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::UnpairedSurrogate
         )
     }
 
     #[test]
-    fn parse_json_array_missing_closing_bracket() {
+    fn parse_json_string_short_unicode_escape_err() {
         assert_eq!(
-            parse_json_array(&"[1, 2 ".chars().collect(), 0),
-            Err(ParseJSONError("Missing closing bracket ']' for array"))
+            parser(r#""\u12""#).parse_json_string(0).unwrap_err().kind(),
+            &ParseJSONErrorKind::InvalidUnicodeEscape
         )
     }
 
     #[test]
-    fn parse_json_array_nested_array() {
+    fn parse_json_string_non_hex_unicode_escape_err() {
         assert_eq!(
-            parse_json_array(&"[1, [2, [3]]]".chars().collect(), 0),
-            Ok((
-                12,
-                vec![
-                    JSONValue::Number(1.0),
-                    JSONValue::Array(vec![
-                        JSONValue::Number(2.0),
-                        JSONValue::Array(vec![JSONValue::Number(3.0),])
-                    ])
-                ]
-            ))
+            parser(r#""\u12g4""#)
+                .parse_json_string(0)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::InvalidUnicodeEscape
         )
     }
 
     #[test]
-    fn parse_json_just_empty_array() {
-        assert_eq!(parse_json("[]"), Ok(JSONValue::Array(vec![])))
+    fn parse_json_just_string() {
+        assert_eq!(
+            parse_json(r#""hello, world!""#),
+            Ok(JSONValue::String("hello, world!".into()))
+        );
     }
 
     #[test]
-    fn parse_json_just_empty_array_with_space() {
-        assert_eq!(parse_json("   [    ]   "), Ok(JSONValue::Array(vec![])))
+    fn parse_json_number_1() {
+        assert_eq!(
+            parser(r#"-1.2e+3"#).parse_json_number(0),
+            Ok((6, Number(-1200f64)))
+        );
     }
 
     #[test]
-    fn parse_json_empty_object() {
-        assert_eq!(parse_json("{}"), Ok(JSONValue::Object(vec![])))
+    fn parse_json_number_2() {
+        assert_eq!(
+            parser(r#"-1.2E-3,"#).parse_json_number(0),
+            Ok((6, Number(-0.0012f64)))
+        );
     }
 
     #[test]
-    fn parse_json_simple_object() {
-        assert_eq!(
-            parse_json(r#"{ "message": "hello!" }"#),
-            Ok(JSONValue::Object(vec![(
-                "message".to_string(),
-                JSONValue::String("hello!".to_string())
-            )]))
-        )
+    fn parse_json_number_plus_outside_exponent() {
+        assert_eq!(parser(r#"1+2"#).parse_json_number(0), Ok((0, Integer(1))));
     }
 
     #[test]
-    fn parse_json_simple_object_multiple_keys() {
+    fn parse_json_number_positive_exponent() {
         assert_eq!(
-            parse_json(r#"{ "message": "things are broken", "success": false}"#),
-            Ok(JSONValue::Object(vec![
-                (
-                    "message".to_string(),
-                    JSONValue::String("things are broken".to_string())
+            parser(r#"1e+5"#).parse_json_number(0),
+            Ok((3, Number(100000f64)))
+        );
+    }
+
+    #[test]
+    fn parse_json_number_negative_exponent() {
+        assert_eq!(
+            parser(r#"1e-5"#).parse_json_number(0),
+            Ok((3, Number(0.00001f64)))
+        );
+    }
+
+    #[test]
+    fn parse_json_integer() {
+        assert_eq!(parse_json("42"), Ok(Integer(42)));
+        assert_eq!(parse_json("-42"), Ok(Integer(-42)));
+    }
+
+    #[test]
+    fn parse_json_integral_float() {
+        assert_eq!(parse_json("42.0"), Ok(Number(42.0)));
+        assert_eq!(parse_json("42e0"), Ok(Number(42.0)));
+    }
+
+    #[test]
+    fn parse_json_integer_keeps_precision() {
+        assert_eq!(
+            parse_json("9007199254740993"),
+            Ok(Integer(9007199254740993))
+        );
+    }
+
+    #[test]
+    fn parse_json_integer_too_big_for_i64() {
+        assert_eq!(
+            parse_json("18446744073709551616"),
+            Ok(Number(18446744073709551616.0))
+        );
+    }
+
+    #[test]
+    fn parse_json_negative_zero() {
+        let parsed = parse_json("-0").unwrap().as_f64().unwrap();
+        assert!(parsed == 0.0 && parsed.is_sign_negative());
+    }
+
+    #[test]
+    fn parse_json_number_leading_zero_err() {
+        assert_eq!(
+            parse_json("01").unwrap_err().kind(),
+            &ParseJSONErrorKind::InvalidNumber
+        );
+        assert_eq!(
+            parse_json("-01").unwrap_err().kind(),
+            &ParseJSONErrorKind::InvalidNumber
+        );
+    }
+
+    #[test]
+    fn parse_json_number_trailing_decimal_point_err() {
+        assert_eq!(
+            parse_json("1.").unwrap_err().kind(),
+            &ParseJSONErrorKind::InvalidNumber
+        );
+        assert_eq!(
+            parse_json("[1.]").unwrap_err().kind(),
+            &ParseJSONErrorKind::InvalidNumber
+        );
+    }
+
+    #[test]
+    fn parse_json_number_leading_decimal_point_err() {
+        assert_eq!(
+            parse_json(".5").unwrap_err().kind(),
+            &ParseJSONErrorKind::InvalidNumber
+        );
+        assert_eq!(
+            parse_json("-.5").unwrap_err().kind(),
+            &ParseJSONErrorKind::ExpectedDigitAfterMinus
+        );
+    }
+
+    #[test]
+    fn parse_json_number_minus_without_digit_err() {
+        for (input, position) in [("-", 1), ("[-,1]", 2), ("-x", 1), (r#"{"a": -}"#, 7)] {
+            let err = parse_json(input).unwrap_err();
+            assert_eq!(
+                err.kind(),
+                &ParseJSONErrorKind::ExpectedDigitAfterMinus,
+                "{}",
+                input
+            );
+            assert_eq!(err.position(), position, "{}", input);
+        }
+        assert_eq!(
+            parser("-,").parse_json_number(0).unwrap_err().to_string(),
+            "Expected digit after '-' at line 1, column 2"
+        );
+    }
+
+    fn with_single_quotes() -> ParseOptions {
+        return ParseOptions {
+            allow_single_quotes: true,
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_single_quotes() {
+        assert_eq!(
+            parse_json_with_options("'hello'", &with_single_quotes()),
+            Ok(String("hello".into()))
+        );
+        assert_eq!(
+            parse_json_with_options(
+                r#"{'a': ['it\'s', "say \'hi\'", '"q"']}"#,
+                &with_single_quotes()
+            ),
+            Ok(Object(vec![(
+                "a".into(),
+                Array(vec![
+                    String("it's".into()),
+                    String("say 'hi'".into()),
+                    String("\"q\"".into())
+                ])
+            )]))
+        );
+        assert_eq!(
+            parse_json_with_options("'open", &with_single_quotes())
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::MissingEndQuote
+        );
+    }
+
+    #[test]
+    fn parse_json_single_quotes_off_by_default() {
+        assert_eq!(
+            parse_json("'hello'").unwrap_err().kind(),
+            &ParseJSONErrorKind::NoValue
+        );
+        assert_eq!(
+            parse_json("{'a': 1}").unwrap_err().kind(),
+            &ParseJSONErrorKind::UnexpectedChar {
+                found: Some('\''),
+                expected: '"'
+            }
+        );
+        assert_eq!(
+            parse_json(r#""it\'s""#).unwrap_err().kind(),
+            &ParseJSONErrorKind::InvalidEscape
+        );
+    }
+
+    fn with_unquoted_keys() -> ParseOptions {
+        return ParseOptions {
+            allow_unquoted_keys: true,
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_unquoted_keys() {
+        assert_eq!(
+            parse_json_with_options(r#"{ name: "x" }"#, &with_unquoted_keys()),
+            Ok(Object(vec![("name".into(), String("x".into()))]))
+        );
+        assert_eq!(
+            parse_json_with_options(
+                r#"{_a1: 1, $b:2, "quoted": {c_$: []}}"#,
+                &with_unquoted_keys()
+            ),
+            parse_json(r#"{"_a1": 1, "$b": 2, "quoted": {"c_$": []}}"#)
+        );
+
+        for input in ["{1a: 1}", "{a-b: 1}", "{a b: 1}", "{-: 1}"] {
+            assert!(
+                parse_json_with_options(input, &with_unquoted_keys()).is_err(),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parse_json_unquoted_keys_off_by_default() {
+        assert_eq!(
+            parse_json(r#"{ name: "x" }"#).unwrap_err().kind(),
+            &ParseJSONErrorKind::UnexpectedChar {
+                found: Some('n'),
+                expected: '"'
+            }
+        );
+    }
+
+    fn with_hex_numbers() -> ParseOptions {
+        return ParseOptions {
+            allow_hex_numbers: true,
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_hex_numbers() {
+        assert_eq!(
+            parse_json_with_options("0xFF", &with_hex_numbers()),
+            Ok(Integer(255))
+        );
+        assert_eq!(
+            parse_json_with_options("[-0x10, 0Xab, 0x0]", &with_hex_numbers()),
+            Ok(Array(vec![Integer(-16), Integer(171), Integer(0)]))
+        );
+        assert_eq!(
+            parse_json_with_options(
+                "[0x7FFFFFFFFFFFFFFF, -0x8000000000000000, 0x10000000000000000]",
+                &with_hex_numbers()
+            ),
+            Ok(Array(vec![
+                Integer(i64::MAX),
+                Integer(i64::MIN),
+                Number(18446744073709551616.0)
+            ]))
+        );
+        let zero = parse_json_with_options("-0x0", &with_hex_numbers())
+            .unwrap()
+            .as_f64()
+            .unwrap();
+        assert!(zero == 0.0 && zero.is_sign_negative());
+
+        for input in ["0x", "0xG", "[0x1.5]", "00xF"] {
+            assert!(
+                parse_json_with_options(input, &with_hex_numbers()).is_err(),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parse_json_hex_numbers_off_by_default() {
+        assert_eq!(
+            parse_json("0xFF").unwrap_err().kind(),
+            &ParseJSONErrorKind::TrailingCharacters
+        );
+        assert_eq!(
+            parse_json("[-0x10]").unwrap_err().kind(),
+            &ParseJSONErrorKind::UnexpectedChar {
+                found: Some('x'),
+                expected: ']'
+            }
+        );
+    }
+
+    #[test]
+    fn parse_json_number_empty_exponent_err() {
+        assert_eq!(
+            parse_json("1e").unwrap_err().kind(),
+            &ParseJSONErrorKind::InvalidNumber
+        );
+        assert_eq!(
+            parse_json("1e+").unwrap_err().kind(),
+            &ParseJSONErrorKind::InvalidNumber
+        );
+    }
+
+    #[test]
+    fn parse_json_number_zeros() {
+        assert_eq!(parse_json("0"), Ok(Integer(0)));
+        assert_eq!(parse_json("0.5"), Ok(Number(0.5)));
+        assert_eq!(parse_json("-0").unwrap().as_f64(), Some(0.0));
+        assert_eq!(parse_json("0e5"), Ok(Number(0.0)));
+    }
+
+    #[test]
+    fn parse_json_number_at_end_of_input() {
+        assert_eq!(parser("123").parse_json_number(0), Ok((2, Integer(123))));
+        assert_eq!(parser("1.5e3").parse_json_value(0), Ok((4, Number(1500.0))));
+        assert_eq!(parse_json("123"), Ok(Integer(123)));
+    }
+
+    #[test]
+    fn parse_json_number_before_array_end() {
+        assert_eq!(parser("[123]").parse_json_number(1), Ok((3, Integer(123))));
+        assert_eq!(
+            parser("[123]").parse_json_array(0),
+            Ok((4, vec![Integer(123)]))
+        );
+        assert_eq!(
+            parser("[123 ]").parse_json_array(0),
+            Ok((5, vec![Integer(123)]))
+        );
+        assert_eq!(parse_json("[123 ]"), Ok(Array(vec![Integer(123)])));
+    }
+
+    #[test]
+    fn parse_json_just_number() {
+        assert_eq!(parse_json(r#"-1.2e+3"#), Ok(JSONValue::Number(-1200f64)));
+    }
+
+    #[test]
+    fn parse_json_null() {
+        assert_eq!(parse_json("null"), Ok(JSONValue::Null));
+    }
+
+    #[test]
+    fn parse_json_true() {
+        assert_eq!(parse_json("true"), Ok(JSONValue::True));
+    }
+
+    #[test]
+    fn parse_json_false() {
+        assert_eq!(parse_json("false"), Ok(JSONValue::False));
+    }
+
+    #[test]
+    fn parse_json_array_empty_array() {
+        assert_eq!(parser("[]").parse_json_array(0), Ok((1, vec![])))
+    }
+
+    #[test]
+    fn parse_json_array_numbers_array() {
+        assert_eq!(
+            parser("[ 1 , 2 , 3 ]").parse_json_array(0),
+            Ok((
+                12,
+                vec![
+                    JSONValue::Integer(1),
+                    JSONValue::Integer(2),
+                    JSONValue::Integer(3),
+                ]
+            ))
+        )
+    }
+
+    #[test]
+    fn parse_json_array_trailing_comma() {
+        assert_eq!(
+            parser("[1, 2,]").parse_json_array(0).unwrap_err().kind(),
+            &ParseJSONErrorKind::NoValue
+        )
+    }
+
+    #[test]
+    fn parse_json_array_double_comma() {
+        assert_eq!(
+            parser("[1, 2,,]").parse_json_array(0).unwrap_err().kind(),
+            &ParseJSONErrorKind::UnexpectedComma
+        )
+    }
+
+    #[test]
+    fn parse_json_array_missing_comma() {
+        assert_eq!(
+            parser("[1, 2  3]").parse_json_array(0).unwrap_err().kind(),
+            &ParseJSONErrorKind::UnexpectedChar {
+                found: Some('3'),
+                expected: ']'
+            }
+        )
+    }
+
+    #[test]
+    fn parse_json_array_missing_closing_bracket() {
+        assert_eq!(
+            parser("[1, 2 ").parse_json_array(0).unwrap_err().kind(),
+            &ParseJSONErrorKind::MissingArrayEnd
+        )
+    }
+
+    #[test]
+    fn parse_json_array_nested_array() {
+        assert_eq!(
+            parser("[1, [2, [3]]]").parse_json_array(0),
+            Ok((
+                12,
+                vec![
+                    JSONValue::Integer(1),
+                    JSONValue::Array(vec![
+                        JSONValue::Integer(2),
+                        JSONValue::Array(vec![JSONValue::Integer(3),])
+                    ])
+                ]
+            ))
+        )
+    }
+
+    #[test]
+    fn parse_json_just_empty_array() {
+        assert_eq!(parse_json("[]"), Ok(JSONValue::Array(vec![])))
+    }
+
+    #[test]
+    fn parse_json_just_empty_array_with_space() {
+        assert_eq!(parse_json("   [    ]   "), Ok(JSONValue::Array(vec![])))
+    }
+
+    #[test]
+    fn parse_json_empty_object() {
+        assert_eq!(parse_json("{}"), Ok(JSONValue::Object(vec![])))
+    }
+
+    #[test]
+    fn parse_json_simple_object() {
+        assert_eq!(
+            parse_json(r#"{ "message": "hello!" }"#),
+            Ok(JSONValue::Object(vec![(
+                "message".into(),
+                JSONValue::String("hello!".into())
+            )]))
+        )
+    }
+
+    #[test]
+    fn parse_json_simple_object_multiple_keys() {
+        assert_eq!(
+            parse_json(fixtures::SIMPLE_OBJECT),
+            Ok(JSONValue::Object(vec![
+                (
+                    "message".into(),
+                    JSONValue::String("things are broken".into())
                 ),
-                ("success".to_string(), JSONValue::False)
+                ("success".into(), JSONValue::False)
             ]))
         )
     }
@@ -468,16 +2080,10 @@ mod tests {
     #[test]
     fn parse_json_nested_object() {
         assert_eq!(
-            parse_json(
-                r#"{
-    "data": {
-        "number": 1
-    }
-}"#
-            ),
+            parse_json(fixtures::NESTED_OBJECT),
             Ok(Object(vec![(
-                "data".to_string(),
-                Object(vec![("number".to_string(), Number(1.0))])
+                "data".into(),
+                Object(vec![("number".into(), Integer(1))])
             )]))
         )
     }
@@ -485,43 +2091,20 @@ mod tests {
     #[test]
     fn parse_json_complex_object_kitchen_sink() {
         assert_eq!(
-            parse_json(
-                r#"{
-    "object": {
-        "thing": 1,
-        "another": 2.0e10,
-        "true": false,
-        "exists": null,
-        "items": [
-            {
-                "type": "item thingo"
-            },
-            true,
-            "hey!",
-            [
-                false,
-                true
-            ]
-        ]
-    }
-}"#
-            ),
+            parse_json(fixtures::KITCHEN_SINK),
             Ok(Object(vec![(
-                "object".to_string(),
+                "object".into(),
                 Object(vec![
-                    ("thing".to_string(), Number(1.0)),
-                    ("another".to_string(), Number(20000000000.0)),
-                    ("true".to_string(), False),
-                    ("exists".to_string(), Null),
+                    ("thing".into(), Integer(1)),
+                    ("another".into(), Number(20000000000.0)),
+                    ("true".into(), False),
+                    ("exists".into(), Null),
                     (
-                        "items".to_string(),
+                        "items".into(),
                         Array(vec![
-                            Object(vec![(
-                                "type".to_string(),
-                                String("item thingo".to_string())
-                            )]),
+                            Object(vec![("type".into(), String("item thingo".into()))]),
                             True,
-                            String("hey!".to_string()),
+                            String("hey!".into()),
                             Array(vec![False, True])
                         ])
                     )
@@ -533,32 +2116,50 @@ mod tests {
     #[test]
     fn parse_json_object_missing_closing_brace() {
         assert_eq!(
-            parse_json_object(&r#"{"a": 1, "b": 2"#.chars().collect(), 0),
-            Err(ParseJSONError("Missing closing brace '}' for object"))
+            parser(r#"{"a": 1, "b": 2"#)
+                .parse_json_object(0)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::MissingObjectEnd
         )
     }
 
     #[test]
     fn parse_json_object_missing_colon() {
         assert_eq!(
-            parse_json_object(&r#"{"a": 1, "b" 2}"#.chars().collect(), 0),
-            Err(ParseJSONError("Expected ':' after object key"))
+            parser(r#"{"a": 1, "b" 2}"#)
+                .parse_json_object(0)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::UnexpectedChar {
+                found: Some('2'),
+                expected: ':'
+            }
         )
     }
 
     #[test]
     fn parse_json_object_double_comma() {
         assert_eq!(
-            parse_json_object(&r#"{"a": 1, "b": 2,,}"#.chars().collect(), 0),
-            Err(ParseJSONError("Unexpected comma"))
+            parser(r#"{"a": 1, "b": 2,,}"#)
+                .parse_json_object(0)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::UnexpectedComma
         )
     }
 
     #[test]
     fn parse_json_object_missing_comma() {
         assert_eq!(
-            parse_json_object(&r#"{"a": 1, "b": 2  "c": 3}"#.chars().collect(), 0),
-            Err(ParseJSONError("Expected '}' to end object"))
+            parser(r#"{"a": 1, "b": 2  "c": 3}"#)
+                .parse_json_object(0)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::UnexpectedChar {
+                found: Some('"'),
+                expected: '}'
+            }
         )
     }
 
@@ -566,4 +2167,1328 @@ mod tests {
     fn parse_json_empty_object_with_space() {
         assert_eq!(parse_json("   {    }   "), Ok(JSONValue::Object(vec![])))
     }
+
+    #[test]
+    fn parse_json_trailing_value_err() {
+        assert_eq!(
+            parse_json("1 2").unwrap_err().kind(),
+            &ParseJSONErrorKind::TrailingCharacters
+        )
+    }
+
+    #[test]
+    fn parse_json_trailing_object_err() {
+        assert_eq!(
+            parse_json("{} {}").unwrap_err().kind(),
+            &ParseJSONErrorKind::TrailingCharacters
+        )
+    }
+
+    #[test]
+    fn parse_json_trailing_whitespace() {
+        assert_eq!(parse_json("true   "), Ok(JSONValue::True))
+    }
+
+    #[test]
+    fn parse_json_error_position_trailing() {
+        assert_eq!(parse_json("1 2").unwrap_err().position(), 2)
+    }
+
+    #[test]
+    fn parse_json_error_position_nested() {
+        let err = parse_json(r#"{"a": [1, 2,,]}"#).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::UnexpectedComma);
+        assert_eq!(err.position(), 12);
+    }
+
+    #[test]
+    fn parse_json_error_position_invalid_escape() {
+        let err = parse_json(r#"["ok", "bad\xescape"]"#).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::InvalidEscape);
+        assert_eq!(err.position(), 11);
+    }
+
+    #[test]
+    fn parse_json_error_display() {
+        assert_eq!(
+            parse_json("[1, 2").unwrap_err().to_string(),
+            "Missing closing bracket ']' for array at line 1, column 6"
+        )
+    }
+
+    #[test]
+    fn parse_json_error_line_and_column() {
+        let err = parse_json(
+            r#"{
+    "fine": 1,
+    "broken": -,
+    "never": "reached"
+}"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::ExpectedDigitAfterMinus);
+        assert_eq!((err.line(), err.column()), (3, 16));
+        assert_eq!(
+            err.to_string(),
+            "Expected digit after '-' at line 3, column 16"
+        );
+    }
+
+    #[test]
+    fn parse_json_error_column_counts_chars() {
+        let err = parse_json(r#"["héllo", x]"#).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::NoValue);
+        assert_eq!(err.position(), 11);
+        assert_eq!(err.column(), 11);
+    }
+
+    #[test]
+    fn parse_json_value_char_indices() {
+        let input = chars(r#"["héllo", "wörld"] trailing"#);
+        assert_eq!(
+            parse_json_value(&input, 0),
+            Ok((
+                17,
+                Array(vec![String("héllo".into()), String("wörld".into())])
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_json_value_from_end_err() {
+        let input = chars("1");
+        let at_end = parse_json_value(&input, 1).unwrap_err();
+        assert_eq!(at_end.kind(), &ParseJSONErrorKind::UnexpectedEof);
+        assert_eq!(at_end.position(), 1);
+        assert_eq!(parse_json_value(&input, 5), Err(at_end));
+    }
+
+    #[test]
+    fn parse_json_value_char_index_errors() {
+        let input = chars("[\"é\", \"ü\", x]");
+        let error = parse_json_value(&input, 0).unwrap_err();
+        assert_eq!(error.position(), 11);
+        assert_eq!(error.column(), 12);
+
+        let input = chars("\"ö\" [1, \"é\",]");
+        let error = parse_json_value(&input, 4).unwrap_err();
+        assert_eq!(error.kind(), &ParseJSONErrorKind::NoValue);
+        assert_eq!(error.position(), 12);
+    }
+
+    /// The system allocator, noting the largest allocation made on each
+    /// thread so a test can check what its own code allocated
+    struct LargestAllocation;
+
+    thread_local! {
+        static LARGEST_ALLOCATION: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    unsafe impl std::alloc::GlobalAlloc for LargestAllocation {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            let _ = LARGEST_ALLOCATION
+                .try_with(|largest| largest.set(largest.get().max(layout.size())));
+            return unsafe { std::alloc::System.alloc(layout) };
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            return unsafe { std::alloc::System.dealloc(ptr, layout) };
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: LargestAllocation = LargestAllocation;
+
+    #[test]
+    fn parse_json_large_array() {
+        let item = r#"{"a": 1, "b": "some text"},"#;
+        let count = 1_000_000 / item.len();
+        let input = format!("[{}null]", item.repeat(count));
+
+        LARGEST_ALLOCATION.with(|largest| largest.set(0));
+        match parse_json(&input) {
+            Ok(Array(items)) => assert_eq!(items.len(), count + 1),
+            other => panic!("expected an array, got {:?}", other),
+        }
+        // A char for every byte of the input would be the largest by far
+        let largest = LARGEST_ALLOCATION.with(|largest| largest.get());
+        assert!(largest < input.len() * std::mem::size_of::<char>());
+    }
+
+    #[test]
+    fn from_str_parses() {
+        assert_eq!("true".parse::<JSONValue>(), Ok(True));
+        assert_eq!(
+            fixtures::SIMPLE_OBJECT.parse::<JSONValue>(),
+            parse_json(fixtures::SIMPLE_OBJECT)
+        );
+    }
+
+    #[test]
+    fn from_str_error() {
+        let err: ParseJSONError = "[1,,]".parse::<JSONValue>().unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::UnexpectedComma);
+    }
+
+    fn nested_arrays(depth: usize) -> std::string::String {
+        return "[".repeat(depth) + &"]".repeat(depth);
+    }
+
+    #[test]
+    fn parse_json_at_max_depth() {
+        assert!(parse_json(&nested_arrays(DEFAULT_MAX_DEPTH)).is_ok());
+    }
+
+    #[test]
+    fn parse_json_past_max_depth_err() {
+        let err = parse_json(&nested_arrays(DEFAULT_MAX_DEPTH + 1)).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::MaxDepthExceeded);
+        assert_eq!(err.position(), DEFAULT_MAX_DEPTH);
+    }
+
+    #[test]
+    fn parse_json_deeply_nested_err_instead_of_overflow() {
+        assert_eq!(
+            parse_json(&nested_arrays(100_000)).unwrap_err().kind(),
+            &ParseJSONErrorKind::MaxDepthExceeded
+        );
+    }
+
+    #[test]
+    fn parse_json_with_options_custom_max_depth() {
+        let options = ParseOptions {
+            max_depth: Some(2),
+            ..ParseOptions::default()
+        };
+        assert!(parse_json_with_options(r#"{"a": [1]}"#, &options).is_ok());
+        assert_eq!(
+            parse_json_with_options(r#"{"a": [[1]]}"#, &options)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::MaxDepthExceeded
+        );
+    }
+
+    #[test]
+    fn parse_json_with_options_no_max_depth() {
+        let options = ParseOptions {
+            max_depth: None,
+            ..ParseOptions::default()
+        };
+        assert!(parse_json_with_options(&nested_arrays(DEFAULT_MAX_DEPTH * 2), &options).is_ok());
+    }
+
+    #[test]
+    fn parse_json_no_max_depth_100k_levels() {
+        let options = ParseOptions {
+            max_depth: None,
+            ..ParseOptions::default()
+        };
+        let input = "[".repeat(50_000)
+            + &r#"{"a": "#.repeat(50_000)
+            + "[]"
+            + &"}".repeat(50_000)
+            + &"]".repeat(50_000);
+        let mut value = parse_json_with_options(&input, &options).unwrap();
+
+        // Dropping the value recurses, so it's taken apart a level at a time
+        let mut levels = 0;
+        loop {
+            value = match value {
+                Array(mut items) if items.len() == 1 => items.pop().unwrap(),
+                Object(mut members) if members.len() == 1 => members.pop().unwrap().1,
+                leaf => {
+                    assert_eq!(leaf, Array(vec![]));
+                    break;
+                }
+            };
+            levels += 1;
+        }
+        assert_eq!(levels, 100_000);
+    }
+
+    fn with_comments() -> ParseOptions {
+        return ParseOptions {
+            allow_comments: true,
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_with_comments() {
+        let input = r#"{
+    /* the first member */ "a": 1, // a trailing comment
+    "b": /* between */ [true /* inside */, null] // "c": 3
+} // after the value"#;
+        assert_eq!(
+            parse_json_with_options(input, &with_comments()),
+            parse_json(r#"{"a": 1, "b": [true, null]}"#)
+        );
+    }
+
+    #[test]
+    fn parse_json_comments_rejected_by_default() {
+        assert_eq!(
+            parse_json("[1 // one\n]").unwrap_err().kind(),
+            &ParseJSONErrorKind::UnexpectedChar {
+                found: Some('/'),
+                expected: ']'
+            }
+        );
+    }
+
+    #[test]
+    fn parse_json_comment_markers_inside_strings() {
+        assert_eq!(
+            parse_json_with_options(
+                r#"["// not a comment", "/* nor this */"]"#,
+                &with_comments()
+            ),
+            Ok(Array(vec![
+                String("// not a comment".into()),
+                String("/* nor this */".into())
+            ]))
+        );
+    }
+
+    #[test]
+    fn parse_json_unclosed_block_comment_err() {
+        assert_eq!(
+            parse_json_with_options("[1 /* never closed ]", &with_comments())
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::UnclosedComment
+        );
+    }
+
+    #[test]
+    fn parse_json_unclosed_block_comment_after_value_err() {
+        for (input, position) in [("1 /* x", 2), ("[1] /*", 4), ("/* x", 0), ("[1, /*]", 4)] {
+            let error = parse_json_with_options(input, &with_comments()).unwrap_err();
+            assert_eq!(
+                error.kind(),
+                &ParseJSONErrorKind::UnclosedComment,
+                "{}",
+                input
+            );
+            assert_eq!(error.position(), position, "{}", input);
+        }
+        assert_eq!(
+            parse_json_with_options("1 // no newline", &with_comments()),
+            Ok(Integer(1))
+        );
+    }
+
+    fn with_trailing_commas() -> ParseOptions {
+        return ParseOptions {
+            allow_trailing_commas: true,
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_trailing_comma_in_array() {
+        assert_eq!(
+            parse_json_with_options("[1,2,]", &with_trailing_commas()),
+            Ok(Array(vec![Integer(1), Integer(2)]))
+        );
+        assert_eq!(
+            parse_json("[1,2,]").unwrap_err().kind(),
+            &ParseJSONErrorKind::NoValue
+        );
+    }
+
+    #[test]
+    fn parse_json_trailing_comma_in_object() {
+        assert_eq!(
+            parse_json_with_options(r#"{"a":1,}"#, &with_trailing_commas()),
+            Ok(Object(vec![("a".into(), Integer(1))]))
+        );
+        assert!(parse_json(r#"{"a":1,}"#).is_err());
+    }
+
+    #[test]
+    fn parse_json_only_one_trailing_comma() {
+        let options = with_trailing_commas();
+        assert_eq!(
+            parse_json_with_options("[1,,]", &options)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::UnexpectedComma
+        );
+        assert_eq!(
+            parse_json_with_options("[,]", &options).unwrap_err().kind(),
+            &ParseJSONErrorKind::UnexpectedComma
+        );
+        assert_eq!(
+            parse_json_with_options(r#"{"a":1,,}"#, &options)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::UnexpectedComma
+        );
+    }
+
+    fn with_non_finite() -> ParseOptions {
+        return ParseOptions {
+            allow_non_finite: true,
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_non_finite_numbers() {
+        let options = with_non_finite();
+        assert_eq!(
+            parse_json_with_options("Infinity", &options),
+            Ok(Number(f64::INFINITY))
+        );
+        assert_eq!(
+            parse_json_with_options("[-Infinity]", &options),
+            Ok(Array(vec![Number(f64::NEG_INFINITY)]))
+        );
+        match parse_json_with_options(r#"{"a": NaN}"#, &options) {
+            Ok(value) => assert!(value.get("a").and_then(JSONValue::as_f64).unwrap().is_nan()),
+            other => panic!("expected an object, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_json_non_finite_numbers_rejected_by_default() {
+        for input in ["NaN", "Infinity", "-Infinity"] {
+            let err = parse_json(input).unwrap_err();
+            assert_eq!(err.kind(), &ParseJSONErrorKind::NonFiniteNumber);
+            assert_eq!(err.position(), 0);
+        }
+    }
+
+    #[test]
+    fn parse_json_misspelled_non_finite_err() {
+        let options = with_non_finite();
+        assert_eq!(
+            parse_json_with_options("Infinit", &options)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::UnexpectedLiteral
+        );
+        assert_eq!(
+            parse_json_with_options("-Inf", &options)
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::UnexpectedLiteral
+        );
+    }
+
+    #[test]
+    fn parse_json_reader_cursor() {
+        let reader = std::io::Cursor::new(fixtures::KITCHEN_SINK.as_bytes());
+        assert_eq!(
+            parse_json_reader(reader),
+            parse_json(fixtures::KITCHEN_SINK)
+        );
+    }
+
+    #[test]
+    fn parse_json_reader_syntax_err() {
+        let reader = std::io::Cursor::new(b"[1, 2");
+        assert_eq!(
+            parse_json_reader(reader).unwrap_err().kind(),
+            &ParseJSONErrorKind::MissingArrayEnd
+        );
+    }
+
+    #[test]
+    fn parse_json_reader_invalid_utf8_err() {
+        let err = parse_json_reader(&b"[\"a\xFF\"]"[..]).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::InvalidUtf8);
+        assert_eq!(err.position(), 3);
+    }
+
+    #[test]
+    fn parse_json_bytes_invalid_continuation_byte() {
+        let bytes = b"{\"name\": \"caf\xC3(\"}";
+        let err = parse_json_bytes(bytes, &ParseOptions::default()).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::InvalidUtf8);
+        assert_eq!(err.position(), 13);
+
+        let options = ParseOptions {
+            on_invalid_utf8: InvalidUtf8::Replace,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            parse_json_bytes(bytes, &options),
+            Ok(Object(vec![("name".into(), String("caf\u{FFFD}(".into()))]))
+        );
+    }
+
+    #[test]
+    fn parse_json_bytes_valid_utf8() {
+        assert_eq!(
+            parse_json_bytes("[\"café\"]".as_bytes(), &ParseOptions::default()),
+            Ok(Array(vec![String("café".into())]))
+        );
+        assert_eq!(
+            parse_json_bytes(b"[1,]", &with_trailing_commas()),
+            Ok(Array(vec![Integer(1)]))
+        );
+    }
+
+    struct BrokenReader;
+
+    impl Read for BrokenReader {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "cable cut",
+            ));
+        }
+    }
+
+    #[test]
+    fn parse_json_reader_io_err() {
+        let err = parse_json_reader((&b"[1, "[..]).chain(BrokenReader)).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &ParseJSONErrorKind::Io {
+                kind: std::io::ErrorKind::BrokenPipe,
+                message: "cable cut".into(),
+            }
+        );
+        assert_eq!(err.position(), 4);
+        assert_eq!(
+            err.to_string(),
+            "Failed to read input: cable cut at line 1, column 5"
+        );
+    }
+
+    /// Hands out its bytes one at a time, so every char longer than a byte
+    /// is split across reads
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl Read for ByteReader<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+            let Some((first, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buffer[0] = *first;
+            self.0 = rest;
+            return Ok(1);
+        }
+    }
+
+    #[test]
+    fn parse_json_reader_chars_split_across_reads() {
+        let input = "[\"café\", \"🦀\"]";
+        assert_eq!(
+            parse_json_reader(ByteReader(input.as_bytes())),
+            parse_json(input)
+        );
+
+        let err = parse_json_reader(ByteReader(b"[\"caf\xC3(\"]")).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::InvalidUtf8);
+        assert_eq!(err.position(), 5);
+
+        let err = parse_json_reader(ByteReader(b"\"caf\xC3")).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::InvalidUtf8);
+        assert_eq!(err.position(), 4);
+    }
+
+    fn with_duplicate_keys(duplicate_keys: DuplicateKeyPolicy) -> ParseOptions {
+        return ParseOptions {
+            duplicate_keys,
+            ..ParseOptions::default()
+        };
+    }
+
+    const DUPLICATE_KEYS: &str = r#"{"a":1,"b":true,"a":2}"#;
+
+    #[test]
+    fn parse_json_duplicate_keys_keep() {
+        let options = with_duplicate_keys(DuplicateKeyPolicy::Keep);
+        assert_eq!(
+            parse_json_with_options(DUPLICATE_KEYS, &options),
+            Ok(Object(vec![
+                ("a".into(), Integer(1)),
+                ("b".into(), True),
+                ("a".into(), Integer(2))
+            ]))
+        );
+    }
+
+    #[test]
+    fn parse_json_duplicate_keys_first() {
+        let options = with_duplicate_keys(DuplicateKeyPolicy::First);
+        assert_eq!(
+            parse_json_with_options(DUPLICATE_KEYS, &options),
+            Ok(Object(vec![("a".into(), Integer(1)), ("b".into(), True)]))
+        );
+    }
+
+    #[test]
+    fn parse_json_duplicate_keys_last() {
+        let options = with_duplicate_keys(DuplicateKeyPolicy::Last);
+        assert_eq!(
+            parse_json_with_options(DUPLICATE_KEYS, &options),
+            Ok(Object(vec![("a".into(), Integer(2)), ("b".into(), True)]))
+        );
+    }
+
+    #[test]
+    fn parse_json_duplicate_keys_error() {
+        let options = with_duplicate_keys(DuplicateKeyPolicy::Error);
+        let err = parse_json_with_options(DUPLICATE_KEYS, &options).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &ParseJSONErrorKind::DuplicateKey {
+                key: "a".to_string()
+            }
+        );
+        assert_eq!(err.position(), 16);
+        assert_eq!(
+            err.to_string(),
+            "Duplicate key \"a\" in object at line 1, column 17"
+        );
+    }
+
+    #[test]
+    fn parse_json_duplicate_keys_in_separate_objects() {
+        let options = with_duplicate_keys(DuplicateKeyPolicy::Error);
+        assert!(parse_json_with_options(r#"[{"a":1},{"a":2,"b":{"a":3}}]"#, &options).is_ok());
+    }
+
+    #[test]
+    fn parse_json_unexpected_eof() {
+        for input in [r#"{"a":"#, "[1,", "{", "[", r#"{"a""#, r#"{"a": 1,"#] {
+            let err = parse_json(input).unwrap_err();
+            assert_eq!(err.kind(), &ParseJSONErrorKind::UnexpectedEof, "{}", input);
+            assert_eq!(err.position(), input.len());
+        }
+    }
+
+    #[test]
+    fn parse_json_unexpected_eof_display() {
+        assert_eq!(
+            parse_json("[1,").unwrap_err().to_string(),
+            "Unexpected end of input at line 1, column 4"
+        );
+    }
+
+    #[test]
+    fn parse_json_missing_end_after_complete_member() {
+        assert_eq!(
+            parse_json("[1").unwrap_err().kind(),
+            &ParseJSONErrorKind::MissingArrayEnd
+        );
+        assert_eq!(
+            parse_json(r#"{"a": 1"#).unwrap_err().kind(),
+            &ParseJSONErrorKind::MissingObjectEnd
+        );
+    }
+
+    #[test]
+    fn parse_json_literal_followed_by_more_err() {
+        for input in ["truex", "nullable", "falsey", "[nullx]", r#"{"a": true1}"#] {
+            assert_eq!(
+                parse_json(input).unwrap_err().kind(),
+                &ParseJSONErrorKind::UnexpectedLiteral,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parse_json_literal_before_delimiters() {
+        assert_eq!(
+            parse_json("[true,false ,null]"),
+            Ok(Array(vec![True, False, Null]))
+        );
+        assert_eq!(
+            parse_json(r#"{"a":null}"#),
+            Ok(Object(vec![("a".into(), Null)]))
+        );
+        assert_eq!(parse_json("true\n"), Ok(True));
+        assert_eq!(
+            parse_json_with_options("[true// yes\n]", &with_comments()),
+            Ok(Array(vec![True]))
+        );
+    }
+
+    fn with_max_elements(max_elements: usize) -> ParseOptions {
+        return ParseOptions {
+            max_elements: Some(max_elements),
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_past_max_elements_err() {
+        let input = "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]";
+        let err = parse_json_with_options(input, &with_max_elements(5)).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::MaxElementsExceeded);
+        // The array itself is the first value, so the fifth item goes over
+        assert_eq!(err.position(), 13);
+    }
+
+    #[test]
+    fn parse_json_at_max_elements() {
+        let input = r#"{"a": [1, 2], "b": null}"#;
+        assert!(parse_json_with_options(input, &with_max_elements(5)).is_ok());
+        assert!(parse_json_with_options(input, &with_max_elements(4)).is_err());
+    }
+
+    fn with_max_object_members(max_object_members: usize) -> ParseOptions {
+        return ParseOptions {
+            max_object_members: Some(max_object_members),
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_past_max_object_members_err() {
+        let input = r#"{"a": 1, "b": 2, "c": 3}"#;
+        let err = parse_json_with_options(input, &with_max_object_members(2)).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::MaxObjectMembersExceeded);
+        assert_eq!(err.kind().to_string(), "Too many object members");
+        assert_eq!(err.position(), 17);
+        assert!(parse_json_with_options(input, &with_max_object_members(3)).is_ok());
+    }
+
+    #[test]
+    fn parse_json_max_object_members_per_object() {
+        let input = r#"{"a": {"x": 1, "y": 2}, "b": [{"z": 3, "w": 4}]}"#;
+        assert!(parse_json_with_options(input, &with_max_object_members(2)).is_ok());
+
+        // Repeated keys only count when they're kept
+        let repeated = r#"{"a": 1, "b": 2, "a": 3}"#;
+        let options = ParseOptions {
+            duplicate_keys: DuplicateKeyPolicy::Last,
+            ..with_max_object_members(2)
+        };
+        assert_eq!(
+            parse_json_with_options(repeated, &options),
+            Ok(Object(vec![
+                ("a".into(), Integer(3)),
+                ("b".into(), Integer(2))
+            ]))
+        );
+        assert!(parse_json_with_options(repeated, &with_max_object_members(2)).is_err());
+    }
+
+    fn requiring_container() -> ParseOptions {
+        return ParseOptions {
+            require_container: true,
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_require_container_rejects_scalars() {
+        let err = parse_json_with_options("  42", &requiring_container()).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::ExpectedContainer);
+        assert_eq!(err.position(), 2);
+        assert_eq!(parse_json("42"), Ok(Integer(42)));
+    }
+
+    #[test]
+    fn parse_json_require_container_accepts_containers() {
+        assert_eq!(
+            parse_json_with_options(r#"{"a": 42}"#, &requiring_container()),
+            parse_json(r#"{"a": 42}"#)
+        );
+        assert_eq!(
+            parse_json_with_options("[42]", &requiring_container()),
+            Ok(Array(vec![Integer(42)]))
+        );
+    }
+
+    #[test]
+    fn parse_json_require_container_empty_input() {
+        assert_eq!(
+            parse_json_with_options("", &requiring_container())
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::EmptyInput
+        );
+    }
+
+    #[test]
+    fn parse_json_only_json_whitespace() {
+        assert_eq!(
+            parse_json(" \t\r\n[ 1 ,\t2 ,\r\n3 ]\n"),
+            Ok(Array(vec![Integer(1), Integer(2), Integer(3)]))
+        );
+        assert_eq!(
+            parse_json("[\u{00A0}1]").unwrap_err().kind(),
+            &ParseJSONErrorKind::NoValue
+        );
+        assert_eq!(
+            parse_json("[1\u{3000}]").unwrap_err().kind(),
+            &ParseJSONErrorKind::UnexpectedChar {
+                found: Some('\u{3000}'),
+                expected: ']'
+            }
+        );
+        assert_eq!(
+            parse_json("true\u{000C}").unwrap_err().kind(),
+            &ParseJSONErrorKind::UnexpectedLiteral
+        );
+        assert_eq!(
+            parse_json("\u{00A0}null").unwrap_err().kind(),
+            &ParseJSONErrorKind::NoValue
+        );
+    }
+
+    #[test]
+    fn parse_json_many_whitespace_separated() {
+        assert_eq!(
+            parse_json_many("{} [] 42"),
+            Ok(vec![Object(vec![]), Array(vec![]), Integer(42)])
+        );
+        assert_eq!(
+            parse_json_many("1 2\ttrue [null]\"x\""),
+            Ok(vec![
+                Integer(1),
+                Integer(2),
+                True,
+                Array(vec![Null]),
+                String("x".into())
+            ])
+        );
+        assert_eq!(parse_json_many(""), Ok(vec![]));
+        assert_eq!(parse_json_many(" \n "), Ok(vec![]));
+    }
+
+    #[test]
+    fn parse_json_many_unseparated_scalars_err() {
+        for (input, kind, position) in [
+            ("1\"a\"", ParseJSONErrorKind::InvalidNumber, 0),
+            ("[] -2.5[]", ParseJSONErrorKind::InvalidNumber, 3),
+            ("1 2{}", ParseJSONErrorKind::InvalidNumber, 2),
+            ("true\"a\"", ParseJSONErrorKind::UnexpectedLiteral, 0),
+        ] {
+            let error = parse_json_many(input).unwrap_err();
+            assert_eq!(error.kind(), &kind, "{}", input);
+            assert_eq!(error.position(), position, "{}", input);
+        }
+        assert_eq!(
+            parse_json_many("\"a\"1 [2]3"),
+            Ok(vec![
+                String("a".into()),
+                Integer(1),
+                Array(vec![Integer(2)]),
+                Integer(3)
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_json_many_ndjson() {
+        let input = "{\"id\": 1, \"ok\": true}\n{\"id\": 2, \"ok\": false}\r\n{\"id\": 3}\n";
+        let values = parse_json_many(input).unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[1].get("ok"), Some(&False));
+        assert_eq!(values[2].get("id"), Some(&Integer(3)));
+
+        let error = parse_json_many("{\"id\": 1}\n{\"id\" 2}\n").unwrap_err();
+        assert_eq!(error.line(), 2);
+        assert_eq!(
+            parse_json_many("[1] ]").unwrap_err().kind(),
+            &ParseJSONErrorKind::NoValue
+        );
+    }
+
+    fn preserving_number_text() -> ParseOptions {
+        return ParseOptions {
+            preserve_number_text: true,
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_preserve_number_text() {
+        let value = parse_json_with_options("9999999999999999", &preserving_number_text());
+        assert_eq!(value, Ok(RawNumber("9999999999999999".to_string())));
+        assert_eq!(value.unwrap().to_string(), "9999999999999999");
+
+        let input = "[1e1000,0.30000000000000000001,-0,2.50,-12E+3,7]";
+        let value = parse_json_with_options(input, &preserving_number_text()).unwrap();
+        assert_eq!(value.to_string(), input);
+        assert_eq!(value.pointer("/0"), Some(&RawNumber("1e1000".to_string())));
+    }
+
+    #[test]
+    fn parse_json_preserve_number_text_still_validates() {
+        let kinds = [
+            ("01", ParseJSONErrorKind::InvalidNumber),
+            ("1.", ParseJSONErrorKind::InvalidNumber),
+            ("-", ParseJSONErrorKind::ExpectedDigitAfterMinus),
+            ("1e", ParseJSONErrorKind::InvalidNumber),
+            (".5", ParseJSONErrorKind::InvalidNumber),
+            ("1.e3", ParseJSONErrorKind::InvalidNumber),
+        ];
+        for (input, kind) in kinds {
+            assert_eq!(
+                parse_json_with_options(input, &preserving_number_text())
+                    .unwrap_err()
+                    .kind(),
+                &kind,
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            parse_json_with_options("NaN", &preserving_number_text())
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::NonFiniteNumber
+        );
+    }
+
+    #[test]
+    fn find_first_error_offsets() {
+        assert_eq!(
+            find_first_error(r#"{"a":}"#),
+            Some((5, ParseJSONErrorKind::NoValue.to_string()))
+        );
+        assert_eq!(
+            find_first_error("[1, 2\n"),
+            Some((6, ParseJSONErrorKind::MissingArrayEnd.to_string()))
+        );
+        assert_eq!(find_first_error(fixtures::KITCHEN_SINK), None);
+        assert_eq!(find_first_error(" null "), None);
+    }
+
+    #[test]
+    fn parse_json_lenient_truncated_object() {
+        let (value, errors) = parse_json_lenient(r#"{"a":1,"b":"#);
+        assert_eq!(value, Some(Object(vec![("a".into(), Integer(1))])));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), &ParseJSONErrorKind::UnexpectedEof);
+        assert_eq!(errors[0].position(), 11);
+
+        let (value, errors) = parse_json_lenient(r#"{"a": [1, {"b": true"#);
+        assert_eq!(value, parse_json(r#"{"a": [1, {"b": true}]}"#).ok());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), &ParseJSONErrorKind::MissingObjectEnd);
+    }
+
+    #[test]
+    fn parse_json_lenient_collects_errors() {
+        let (value, errors) = parse_json_lenient(r#"[1, tru, "x\q", {"a": [2}, 3 4, , 5] x"#);
+        assert_eq!(
+            value,
+            Some(Array(vec![
+                Integer(1),
+                Object(vec![("a".into(), Array(vec![Integer(2)]))]),
+                Integer(3),
+                Integer(5)
+            ]))
+        );
+        let kinds: Vec<_> = errors.iter().map(ParseJSONError::kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &ParseJSONErrorKind::UnexpectedLiteral,
+                &ParseJSONErrorKind::InvalidEscape,
+                &ParseJSONErrorKind::UnexpectedChar {
+                    found: Some('}'),
+                    expected: ']'
+                },
+                &ParseJSONErrorKind::UnexpectedChar {
+                    found: Some('4'),
+                    expected: ']'
+                },
+                &ParseJSONErrorKind::UnexpectedComma,
+                &ParseJSONErrorKind::TrailingCharacters,
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_json_lenient_too_deep_next_to_sibling_at_limit() {
+        // The first item goes one level past the limit, the second just
+        // reaches it
+        let too_deep = format!("{}[[]]{}", "[".repeat(126), "]".repeat(126));
+        let at_limit = format!("{}{}", "[".repeat(127), "]".repeat(127));
+        let input = format!("[{}, {}]", too_deep, at_limit);
+
+        let (value, errors) = parse_json_lenient(&input);
+        assert!(value.is_some());
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].kind(), &ParseJSONErrorKind::MaxDepthExceeded);
+        assert_eq!(errors[0].position(), 128);
+        assert!(parse_json(&format!("[{}]", at_limit)).is_ok());
+    }
+
+    #[test]
+    fn parse_json_lenient_valid_and_hopeless_input() {
+        assert_eq!(
+            parse_json_lenient(fixtures::KITCHEN_SINK),
+            (parse_json(fixtures::KITCHEN_SINK).ok(), vec![])
+        );
+
+        // A closer that doesn't match anything open is skipped
+        let (value, errors) = parse_json_lenient("[2}, 3]");
+        assert_eq!(value, Some(Array(vec![Integer(2), Integer(3)])));
+        assert_eq!(errors.len(), 1);
+
+        let (value, errors) = parse_json_lenient("");
+        assert_eq!(value, None);
+        assert_eq!(errors[0].kind(), &ParseJSONErrorKind::EmptyInput);
+
+        let (value, errors) = parse_json_lenient(r#"{"a" 1, "b": 2, 3: 4}"#);
+        assert_eq!(value, Some(Object(vec![("b".into(), Integer(2))])));
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn parse_json_empty_input() {
+        for input in ["", "   ", " \n\t\r\n"] {
+            let err = parse_json(input).unwrap_err();
+            assert_eq!(err.kind(), &ParseJSONErrorKind::EmptyInput, "{:?}", input);
+            assert_eq!(err.position(), input.len());
+        }
+        assert_eq!(parse_json("[ ]"), Ok(Array(vec![])));
+        assert_eq!(
+            parse_json_with_options("  // nothing", &with_comments())
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::EmptyInput
+        );
+    }
+
+    fn rejecting_non_finite_results() -> ParseOptions {
+        return ParseOptions {
+            reject_non_finite_results: true,
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_reject_non_finite_results() {
+        for input in ["1e400", "-1e400", "[1, 123456789e999]"] {
+            assert_eq!(
+                parse_json_with_options(input, &rejecting_non_finite_results())
+                    .unwrap_err()
+                    .kind(),
+                &ParseJSONErrorKind::NumberOutOfRange,
+                "{}",
+                input
+            );
+        }
+        let err = parse_json_with_options("[1, -1e400]", &rejecting_non_finite_results());
+        assert_eq!(err.unwrap_err().position(), 4);
+
+        assert_eq!(
+            parse_json_with_options(
+                "[1e308, 1e-400, 99999999999999999999]",
+                &rejecting_non_finite_results()
+            ),
+            Ok(Array(vec![Number(1e308), Number(0.0), Number(1e20)]))
+        );
+        assert_eq!(parse_json("1e400"), Ok(Number(f64::INFINITY)));
+    }
+
+    #[test]
+    fn parse_json_prefix_offsets() {
+        let input = "{}trailing";
+        let (value, offset) = parse_json_prefix(input).unwrap();
+        assert_eq!(value, Object(vec![]));
+        assert_eq!(offset, 2);
+        assert_eq!(&input[offset..], "trailing");
+
+        assert_eq!(
+            parse_json_prefix("  [1, \"\u{e9}\"] {not json"),
+            Ok((Array(vec![Integer(1), String("\u{e9}".into())]), 11))
+        );
+        assert_eq!(parse_json_prefix("12;"), Ok((Integer(12), 2)));
+        assert_eq!(parse_json_prefix("null, 1"), Ok((Null, 4)));
+    }
+
+    #[test]
+    fn parse_json_prefix_errors() {
+        assert_eq!(
+            parse_json_prefix("  ").unwrap_err().kind(),
+            &ParseJSONErrorKind::EmptyInput
+        );
+        assert_eq!(
+            parse_json_prefix("[1, 2").unwrap_err().kind(),
+            &ParseJSONErrorKind::MissingArrayEnd
+        );
+        assert_eq!(
+            parse_json_prefix("nullx").unwrap_err().kind(),
+            &ParseJSONErrorKind::UnexpectedLiteral
+        );
+    }
+
+    #[test]
+    fn parse_json_strict_rejects_borderline_input() {
+        use ParseJSONErrorKind::*;
+        let duplicate_a = || DuplicateKey {
+            key: "a".to_string(),
+        };
+        let cases = [
+            (r#"{"a": 1, "a": 2}"#, duplicate_a()),
+            (r#"[{"b": {"a": 1, "a": 1}}]"#, duplicate_a()),
+            ("01", InvalidNumber),
+            ("[-00.5]", InvalidNumber),
+            ("1e400", NumberOutOfRange),
+            ("[-1e400]", NumberOutOfRange),
+            ("NaN", NonFiniteNumber),
+            ("[Infinity]", NonFiniteNumber),
+            ("\"tab\there\"", UnescapedControlCharacter),
+            ("\"nul \u{0}\"", UnescapedControlCharacter),
+            ("{} {}", TrailingCharacters),
+            ("[1] x", TrailingCharacters),
+            ("[1,]", NoValue),
+            ("// comment\n1", NoValue),
+        ];
+        for (input, kind) in cases {
+            assert_eq!(
+                parse_json_with_options(input, &ParseOptions::strict())
+                    .unwrap_err()
+                    .kind(),
+                &kind,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parse_json_strict_accepts_plain_json() {
+        let strict = ParseOptions::strict();
+        assert_eq!(
+            parse_json_with_options(fixtures::KITCHEN_SINK, &strict),
+            parse_json(fixtures::KITCHEN_SINK)
+        );
+        assert_eq!(
+            parse_json_with_options(r#"[{"a": 1}, {"a": 1.7976931348623157e308}]"#, &strict)
+                .map(|value| value.pointer("/1/a").cloned()),
+            Ok(Some(Number(f64::MAX)))
+        );
+    }
+
+    fn with_max_string_length(max: usize) -> ParseOptions {
+        return ParseOptions {
+            max_string_length: Some(max),
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_max_string_length() {
+        let options = with_max_string_length(10);
+        assert_eq!(
+            parse_json_with_options(r#"["0123456789"]"#, &options),
+            Ok(Array(vec![String("0123456789".into())]))
+        );
+
+        let err = parse_json_with_options(r#"["0123456789a"]"#, &options).unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::StringTooLong);
+        assert_eq!(err.position(), 1);
+        assert_eq!(
+            err.to_string(),
+            "String exceeds maximum length at line 1, column 2"
+        );
+    }
+
+    #[test]
+    fn parse_json_max_string_length_stops_early() {
+        let options = with_max_string_length(4);
+        // The string is never closed, but that's not found since scanning
+        // stops at the limit
+        let err = parse_json_with_options(&format!("\"{}", "a".repeat(1000)), &options);
+        assert_eq!(err.unwrap_err().kind(), &ParseJSONErrorKind::StringTooLong);
+
+        for input in [r#"{"long key": 1}"#, r#"["\n\n\n"]"#] {
+            assert_eq!(
+                parse_json_with_options(input, &options).unwrap_err().kind(),
+                &ParseJSONErrorKind::StringTooLong,
+                "{}",
+                input
+            );
+        }
+        assert!(parse_json_with_options(r#"{"key": "\n\n"}"#, &options).is_ok());
+        assert_eq!(
+            parse_json_with_options(r#""\n""#, &with_max_string_length(1))
+                .unwrap_err()
+                .kind(),
+            &ParseJSONErrorKind::StringTooLong
+        );
+    }
+
+    fn interning_strings() -> ParseOptions {
+        return ParseOptions {
+            intern_strings: true,
+            ..ParseOptions::default()
+        };
+    }
+
+    #[test]
+    fn parse_json_intern_strings_shares_values() {
+        let value = parse_json_with_options(
+            r#"[{"type": "item", "name": "a"}, {"type": "item", "name": "item"}, "a"]"#,
+            &interning_strings(),
+        )
+        .unwrap();
+        let strings = [
+            value.pointer("/0/type"),
+            value.pointer("/1/type"),
+            value.pointer("/1/name"),
+            value.pointer("/0/name"),
+            value.pointer("/2"),
+        ]
+        .map(|string| match string {
+            Some(String(string)) => string,
+            _ => panic!("expected a string"),
+        });
+        assert!(strings[0].ptr_eq(strings[1]));
+        assert!(strings[0].ptr_eq(strings[2]));
+        assert!(strings[3].ptr_eq(strings[4]));
+        assert!(!strings[0].ptr_eq(strings[3]));
+
+        assert_eq!(
+            Ok(value),
+            parse_json(r#"[{"type": "item", "name": "a"}, {"type": "item", "name": "item"}, "a"]"#)
+        );
+    }
+
+    /// Bytes held by the distinct allocations behind the "type" strings
+    fn type_string_bytes(value: &JSONValue) -> usize {
+        let mut allocations = HashSet::new();
+        for item in value.elements() {
+            if let Some(String(string)) = item.get("type") {
+                allocations.insert((string.as_ptr(), string.len()));
+            }
+        }
+        return allocations.iter().map(|(_, len)| len).sum();
+    }
+
+    #[test]
+    fn parse_json_intern_strings_memory() {
+        let input = format!(
+            "[{}]",
+            (0..1000)
+                .map(|id| format!(r#"{{"type": "item", "id": {}}}"#, id))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        let owned = parse_json(&input).unwrap();
+        let interned = parse_json_with_options(&input, &interning_strings()).unwrap();
+        assert_eq!(type_string_bytes(&owned), 4000);
+        assert_eq!(type_string_bytes(&interned), 4);
+        assert_eq!(owned, interned);
+    }
+
+    #[test]
+    fn escape_and_unescape_json_string() {
+        let cases = [
+            ("say \"hi\"", r#""say \"hi\"""#),
+            ("back\\slash", r#""back\\slash""#),
+            ("two\nlines\r\ttabbed", r#""two\nlines\r\ttabbed""#),
+            ("bell\u{07}", r#""bell\u0007""#),
+            ("", r#""""#),
+        ];
+        for (string, literal) in cases {
+            assert_eq!(escape_json_string(string), literal);
+            assert_eq!(unescape_json_string(literal), Ok(string.to_string()));
+        }
+
+        assert_eq!(
+            unescape_json_string(r#""caf\u00e9 \ud83d\ude00 \/""#),
+            Ok("caf\u{e9} \u{1F600} /".to_string())
+        );
+        let tricky = "\u{1F600}\"\\\u{1F}";
+        assert_eq!(
+            unescape_json_string(&escape_json_string(tricky)),
+            Ok(tricky.to_string())
+        );
+    }
+
+    #[test]
+    fn unescape_json_string_errors() {
+        let cases = [
+            (r#""bad \x""#, ParseJSONErrorKind::InvalidEscape),
+            (r#""open"#, ParseJSONErrorKind::MissingEndQuote),
+            (r#""a" "#, ParseJSONErrorKind::TrailingCharacters),
+            (r#""\u12""#, ParseJSONErrorKind::InvalidUnicodeEscape),
+            (
+                "plain",
+                ParseJSONErrorKind::UnexpectedChar {
+                    found: Some('p'),
+                    expected: '"',
+                },
+            ),
+            ("", ParseJSONErrorKind::UnexpectedEof),
+        ];
+        for (literal, kind) in cases {
+            assert_eq!(
+                unescape_json_string(literal).unwrap_err().kind(),
+                &kind,
+                "{}",
+                literal
+            );
+        }
+    }
+
+    #[test]
+    fn parse_number_whole_input() {
+        // Compared with the std parse, since clippy takes 3.14 for a bad PI
+        assert_eq!(parse_number("3.14"), Ok("3.14".parse().unwrap()));
+        assert_eq!(parse_number("42"), Ok(42.0));
+        assert_eq!(parse_number("-1.5e3"), Ok(-1500.0));
+        let zero = parse_number("-0").unwrap();
+        assert!(zero == 0.0 && zero.is_sign_negative());
+    }
+
+    #[test]
+    fn parse_number_errors() {
+        let err = parse_number("3.14abc").unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::TrailingCharacters);
+        assert_eq!(err.position(), 4);
+
+        for (number, kind) in [
+            ("3.14 ", ParseJSONErrorKind::TrailingCharacters),
+            (" 1", ParseJSONErrorKind::InvalidNumber),
+            ("01", ParseJSONErrorKind::InvalidNumber),
+            ("NaN", ParseJSONErrorKind::InvalidNumber),
+            ("abc", ParseJSONErrorKind::InvalidNumber),
+            ("", ParseJSONErrorKind::InvalidNumber),
+        ] {
+            assert_eq!(
+                parse_number(number).unwrap_err().kind(),
+                &kind,
+                "{}",
+                number
+            );
+        }
+    }
+
+    #[test]
+    fn parse_json_skips_leading_bom() {
+        assert_eq!(parse_json("\u{FEFF}{}"), Ok(Object(vec![])));
+        assert_eq!(parse_json("\u{FEFF}  [1]\n"), Ok(Array(vec![Integer(1)])));
+        assert_eq!(parse_json_reader("\u{FEFF}true".as_bytes()), Ok(True));
+        assert_eq!(parse_json_prefix("\u{FEFF}1 x"), Ok((Integer(1), 4)));
+        assert_eq!(
+            parse_json_many("\u{FEFF}1 2"),
+            Ok(vec![Integer(1), Integer(2)])
+        );
+
+        assert_eq!(
+            parse_json("\u{FEFF}").unwrap_err().kind(),
+            &ParseJSONErrorKind::EmptyInput
+        );
+        let err = parse_json("[\u{FEFF}1]").unwrap_err();
+        assert_eq!(err.kind(), &ParseJSONErrorKind::NoValue);
+        assert_eq!(
+            parse_json("\u{FEFF}\u{FEFF}{}").unwrap_err().kind(),
+            &ParseJSONErrorKind::NoValue
+        );
+        assert_eq!(
+            parse_json("{}\u{FEFF}").unwrap_err().kind(),
+            &ParseJSONErrorKind::TrailingCharacters
+        );
+    }
 }
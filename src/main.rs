@@ -1,6 +1,9 @@
+use std::error::Error;
+
 use json_pkler::parse_json;
 
-fn main() {
+fn main() -> Result<(), Box<dyn Error>> {
     const BENCH_DATA_001: &str = include_str!("../bench_data/006_beeg_formatted.json");
-    let _ = parse_json(BENCH_DATA_001).unwrap();
+    let _ = parse_json(BENCH_DATA_001)?;
+    Ok(())
 }
@@ -0,0 +1,291 @@
+use crate::value::{parse_array_index, pointer_segments};
+use crate::{JSONValue, ParseJSONError, ParseJSONErrorKind};
+
+impl JSONValue {
+    /// Applies a JSON Patch (RFC 6902), an array of `add`, `remove`,
+    /// `replace`, `move`, `copy` and `test` operations, in order. `test`
+    /// compares with `semantic_eq`. When any operation fails, none of them
+    /// are applied
+    pub fn apply_patch(&mut self, patch: &JSONValue) -> Result<(), ParseJSONError> {
+        let JSONValue::Array(operations) = patch else {
+            return Err(invalid_patch());
+        };
+
+        let mut patched = self.clone();
+        for operation in operations {
+            patched.apply_operation(operation)?;
+        }
+
+        *self = patched;
+        return Ok(());
+    }
+
+    fn apply_operation(&mut self, operation: &JSONValue) -> Result<(), ParseJSONError> {
+        let op = string_member(operation, "op")?;
+        let path = string_member(operation, "path")?;
+
+        return match op {
+            "add" => self.patch_add(path, member(operation, "value")?.clone()),
+            "remove" => self.patch_remove(path).map(|_| ()),
+            "replace" => {
+                *self.patch_lookup(path)? = member(operation, "value")?.clone();
+                Ok(())
+            }
+            "move" => {
+                let from = string_member(operation, "from")?;
+                // A value can't be moved into itself
+                if path.len() > from.len()
+                    && path.starts_with(from)
+                    && path[from.len()..].starts_with('/')
+                {
+                    return Err(ParseJSONError::new(
+                        ParseJSONErrorKind::PathConflict,
+                        path.as_bytes(),
+                        from.len(),
+                    ));
+                }
+                let value = self.patch_remove(from)?;
+                self.patch_add(path, value)
+            }
+            "copy" => {
+                let value = self
+                    .patch_lookup(string_member(operation, "from")?)?
+                    .clone();
+                self.patch_add(path, value)
+            }
+            "test" => {
+                if self
+                    .patch_lookup(path)?
+                    .semantic_eq(member(operation, "value")?)
+                {
+                    Ok(())
+                } else {
+                    let kind = ParseJSONErrorKind::PatchTestFailed;
+                    Err(ParseJSONError::new(kind, path.as_bytes(), 0))
+                }
+            }
+            _ => Err(invalid_patch()),
+        };
+    }
+
+    /// Finds the value at `path`, like `pointer`, but mutably and with an
+    /// error saying which segment wasn't found
+    fn patch_lookup(&mut self, path: &str) -> Result<&mut JSONValue, ParseJSONError> {
+        let segments = pointer_segments(path)?;
+        return self.lookup_segments(path, &segments);
+    }
+
+    fn lookup_segments(
+        &mut self,
+        path: &str,
+        segments: &[(usize, String)],
+    ) -> Result<&mut JSONValue, ParseJSONError> {
+        let mut value = self;
+        for (offset, segment) in segments {
+            let found = match value {
+                JSONValue::Object(members) => members
+                    .iter_mut()
+                    .find(|(key, _)| key == segment)
+                    .map(|(_, value)| value),
+                JSONValue::Array(items) => {
+                    parse_array_index(segment).and_then(|i| items.get_mut(i))
+                }
+                _ => None,
+            };
+            value = found.ok_or_else(|| not_found(path, *offset))?;
+        }
+        return Ok(value);
+    }
+
+    /// Adds `value` as a new member, replacing one with the same key, or
+    /// inserts it as an item, moving the ones after it along. The parent has
+    /// to exist already
+    fn patch_add(&mut self, path: &str, value: JSONValue) -> Result<(), ParseJSONError> {
+        let segments = pointer_segments(path)?;
+        let Some(((offset, last), parent)) = segments.split_last() else {
+            *self = value;
+            return Ok(());
+        };
+
+        match self.lookup_segments(path, parent)? {
+            JSONValue::Object(members) => match members.iter().position(|(key, _)| key == last) {
+                Some(position) => members[position].1 = value,
+                None => members.push((last.as_str().into(), value)),
+            },
+            JSONValue::Array(items) => {
+                let index = match last.as_str() {
+                    "-" => Some(items.len()),
+                    last => parse_array_index(last),
+                };
+                match index {
+                    Some(index) if index <= items.len() => items.insert(index, value),
+                    _ => return Err(not_found(path, *offset)),
+                }
+            }
+            _ => {
+                let kind = ParseJSONErrorKind::PathConflict;
+                return Err(ParseJSONError::new(kind, path.as_bytes(), *offset));
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// Removes the value at `path`, moving any items after it back. The
+    /// whole document can't be removed
+    fn patch_remove(&mut self, path: &str) -> Result<JSONValue, ParseJSONError> {
+        let segments = pointer_segments(path)?;
+        let Some(((offset, last), parent)) = segments.split_last() else {
+            let kind = ParseJSONErrorKind::PathConflict;
+            return Err(ParseJSONError::new(kind, path.as_bytes(), 0));
+        };
+
+        let removed = match self.lookup_segments(path, parent)? {
+            JSONValue::Object(members) => members
+                .iter()
+                .position(|(key, _)| key == last)
+                .map(|position| members.remove(position).1),
+            JSONValue::Array(items) => parse_array_index(last)
+                .filter(|index| *index < items.len())
+                .map(|index| items.remove(index)),
+            _ => None,
+        };
+        return removed.ok_or_else(|| not_found(path, *offset));
+    }
+}
+
+fn member<'a>(operation: &'a JSONValue, key: &str) -> Result<&'a JSONValue, ParseJSONError> {
+    return operation.get(key).ok_or_else(invalid_patch);
+}
+
+fn string_member<'a>(operation: &'a JSONValue, key: &str) -> Result<&'a str, ParseJSONError> {
+    return member(operation, key)?.as_str().ok_or_else(invalid_patch);
+}
+
+fn invalid_patch() -> ParseJSONError {
+    return ParseJSONError::new(ParseJSONErrorKind::InvalidPatch, &[], 0);
+}
+
+fn not_found(path: &str, offset: usize) -> ParseJSONError {
+    return ParseJSONError::new(ParseJSONErrorKind::PathNotFound, path.as_bytes(), offset);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+    use JSONValue::*;
+
+    fn patch(text: &str) -> JSONValue {
+        return parse_json(text).unwrap();
+    }
+
+    #[test]
+    fn apply_add_remove_replace() {
+        let mut value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        value
+            .apply_patch(&patch(
+                r#"[
+                    {"op": "add", "path": "/object/added", "value": [1]},
+                    {"op": "add", "path": "/object/items/1", "value": "inserted"},
+                    {"op": "add", "path": "/object/items/-", "value": null},
+                    {"op": "remove", "path": "/object/exists"},
+                    {"op": "remove", "path": "/object/items/3"},
+                    {"op": "replace", "path": "/object/thing", "value": {"x": 2}}
+                ]"#,
+            ))
+            .unwrap();
+        assert_eq!(
+            value,
+            parse_json(
+                r#"{"object": {"thing": {"x": 2}, "another": 2.0e10, "true": false,
+                    "items": [{"type": "item thingo"}, "inserted", true, [false, true], null],
+                    "added": [1]}}"#
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn apply_move_copy_test() {
+        let mut value = parse_json(r#"{"a": {"b": 1}, "c": [2, 3]}"#).unwrap();
+        value
+            .apply_patch(&patch(
+                r#"[
+                    {"op": "test", "path": "/a", "value": {"b": 1.0}},
+                    {"op": "move", "from": "/a/b", "path": "/c/0"},
+                    {"op": "copy", "from": "/c", "path": "/d"},
+                    {"op": "test", "path": "/d", "value": [1, 2, 3]}
+                ]"#,
+            ))
+            .unwrap();
+        assert_eq!(
+            value,
+            parse_json(r#"{"a": {}, "c": [1, 2, 3], "d": [1, 2, 3]}"#).unwrap()
+        );
+
+        value
+            .apply_patch(&patch(r#"[{"op": "replace", "path": "", "value": true}]"#))
+            .unwrap();
+        assert_eq!(value, True);
+    }
+
+    #[test]
+    fn failed_patch_leaves_value_unchanged() {
+        let original = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let failing = [
+            (
+                r#"[{"op": "remove", "path": "/object/thing"}, {"op": "test", "path": "/object/another", "value": 1}]"#,
+                ParseJSONErrorKind::PatchTestFailed,
+            ),
+            (
+                r#"[{"op": "add", "path": "/object/missing/x", "value": 1}]"#,
+                ParseJSONErrorKind::PathNotFound,
+            ),
+            (
+                r#"[{"op": "add", "path": "/object/items/9", "value": 1}]"#,
+                ParseJSONErrorKind::PathNotFound,
+            ),
+            (
+                r#"[{"op": "remove", "path": "/object/nope"}]"#,
+                ParseJSONErrorKind::PathNotFound,
+            ),
+            (
+                r#"[{"op": "replace", "path": "object", "value": 1}]"#,
+                ParseJSONErrorKind::InvalidPointer,
+            ),
+            (
+                r#"[{"op": "add", "path": "/object/thing/x", "value": 1}]"#,
+                ParseJSONErrorKind::PathConflict,
+            ),
+            (
+                r#"[{"op": "move", "from": "/object", "path": "/object/items/0"}]"#,
+                ParseJSONErrorKind::PathConflict,
+            ),
+            (
+                r#"[{"op": "frobnicate", "path": "/object"}]"#,
+                ParseJSONErrorKind::InvalidPatch,
+            ),
+            (
+                r#"[{"op": "add", "path": "/object/x"}]"#,
+                ParseJSONErrorKind::InvalidPatch,
+            ),
+            (
+                r#"{"op": "remove", "path": "/object"}"#,
+                ParseJSONErrorKind::InvalidPatch,
+            ),
+        ];
+
+        for (text, kind) in failing {
+            let mut value = original.clone();
+            assert_eq!(value.apply_patch(&patch(text)).unwrap_err().kind(), &kind);
+            assert_eq!(value, original);
+        }
+
+        let mut value = original.clone();
+        let error = value
+            .apply_patch(&patch(r#"[{"op": "remove", "path": "/object/nope/x"}]"#))
+            .unwrap_err();
+        assert_eq!(error.position(), 8);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{JSONValue, ParseJSONError, ParseJSONErrorKind};
+
+/// One step of a JSONPath query
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    /// `.key` or `['key']`
+    Key(String),
+    /// `[index]`, counting from the end when negative
+    Index(i64),
+    /// `[*]` or `.*`
+    Wildcard,
+}
+
+impl JSONValue {
+    /// Finds every value a JSONPath query matches, like
+    /// `$.object.items[*].type`. Only a subset of JSONPath is understood:
+    /// the query starts with `$` for this value, and then goes through
+    /// `.key` or `['key']` for an object member, `[index]` for an array
+    /// item, with negative indices counting from the end, and `[*]` or `.*`
+    /// for every item or member value. Steps that find nothing, like a key
+    /// on an array, just match nothing, so only a query that can't be read
+    /// is an error. Like `get`, a key an object has more than once matches
+    /// its first member
+    pub fn query(&self, path: &str) -> Result<Vec<&JSONValue>, ParseJSONError> {
+        let mut matches = vec![self];
+        for selector in parse_query(path)? {
+            matches = matches
+                .into_iter()
+                .flat_map(|value| select(value, &selector))
+                .collect();
+        }
+        return Ok(matches);
+    }
+}
+
+/// The values `selector` picks out of `value`
+fn select<'a>(value: &'a JSONValue, selector: &Selector) -> Vec<&'a JSONValue> {
+    return match (value, selector) {
+        (JSONValue::Object(_), Selector::Key(key)) => value.get(key).into_iter().collect(),
+        (JSONValue::Array(items), Selector::Index(index)) => {
+            let index = if *index < 0 {
+                items.len().checked_sub(index.unsigned_abs() as usize)
+            } else {
+                Some(*index as usize)
+            };
+            index
+                .and_then(|index| items.get(index))
+                .into_iter()
+                .collect()
+        }
+        (JSONValue::Object(members), Selector::Wildcard) => {
+            members.iter().map(|(_, value)| value).collect()
+        }
+        (JSONValue::Array(items), Selector::Wildcard) => items.iter().collect(),
+        _ => vec![],
+    };
+}
+
+/// Splits a query into its selectors, failing with an `InvalidQuery` error
+/// at the byte offset into `path` where it stops making sense
+fn parse_query(path: &str) -> Result<Vec<Selector>, ParseJSONError> {
+    let bytes = path.as_bytes();
+    let invalid = |position: usize| {
+        return ParseJSONError::new(ParseJSONErrorKind::InvalidQuery, bytes, position);
+    };
+
+    if bytes.first() != Some(&b'$') {
+        return Err(invalid(0));
+    }
+
+    let mut selectors = vec![];
+    let mut i = 1;
+    while let Some(&byte) = bytes.get(i) {
+        match byte {
+            b'.' if bytes.get(i + 1) == Some(&b'*') => {
+                selectors.push(Selector::Wildcard);
+                i += 2;
+            }
+            b'.' => {
+                let name_end = path[i + 1..]
+                    .find(['.', '['])
+                    .map_or(path.len(), |offset| i + 1 + offset);
+                if name_end == i + 1 {
+                    return Err(invalid(i + 1));
+                }
+                selectors.push(Selector::Key(path[i + 1..name_end].to_string()));
+                i = name_end;
+            }
+            b'[' => {
+                let (selector, bracket_end) =
+                    parse_bracket(path, i + 1).ok_or_else(|| invalid(i))?;
+                selectors.push(selector);
+                i = bracket_end + 1;
+            }
+            _ => return Err(invalid(i)),
+        }
+    }
+
+    return Ok(selectors);
+}
+
+/// Given the position just inside a `[`, returns what the brackets hold and
+/// the index of the closing `]`
+fn parse_bracket(path: &str, from: usize) -> Option<(Selector, usize)> {
+    let bytes = path.as_bytes();
+    match bytes.get(from)? {
+        b'*' if bytes.get(from + 1) == Some(&b']') => {
+            return Some((Selector::Wildcard, from + 1));
+        }
+        &quote @ (b'\'' | b'"') => {
+            // A backslash escapes the quote or another backslash
+            let mut key = String::new();
+            let mut chars = path[from + 1..].char_indices();
+            while let Some((offset, ch)) = chars.next() {
+                match ch {
+                    '\\' => key.push(chars.next()?.1),
+                    ch if ch == quote as char => {
+                        let bracket_end = from + 1 + offset + 1;
+                        return (bytes.get(bracket_end) == Some(&b']'))
+                            .then_some((Selector::Key(key), bracket_end));
+                    }
+                    ch => key.push(ch),
+                }
+            }
+            return None;
+        }
+        _ => {
+            let bracket_end = from + path[from..].find(']')?;
+            let index = path[from..bracket_end].parse().ok()?;
+            return Some((Selector::Index(index), bracket_end));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+    use JSONValue::*;
+
+    #[test]
+    fn query_kitchen_sink_items_type() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        assert_eq!(
+            value.query("$.object.items[*].type"),
+            Ok(vec![&String("item thingo".into())])
+        );
+        assert_eq!(
+            value.query("$['object'][\"items\"][3][-1]"),
+            Ok(vec![&True])
+        );
+        assert_eq!(value.query("$"), Ok(vec![&value]));
+    }
+
+    #[test]
+    fn query_wildcards() {
+        let value =
+            parse_json(r#"{"list": [{"n": 1}, {"n": 2}, {"m": 3}], "o": {"a": 4, "b": [5]}}"#)
+                .unwrap();
+        assert_eq!(
+            value.query("$.list[*].n"),
+            Ok(vec![&Integer(1), &Integer(2)])
+        );
+        assert_eq!(
+            value.query("$.o.*"),
+            Ok(vec![&Integer(4), &Array(vec![Integer(5)])])
+        );
+        assert_eq!(
+            value.query("$[*][*][*]"),
+            Ok(vec![&Integer(1), &Integer(2), &Integer(3), &Integer(5)])
+        );
+        assert_eq!(value.query("$.list[3]"), Ok(vec![]));
+        assert_eq!(value.query("$.list[-4]"), Ok(vec![]));
+        assert_eq!(value.query("$.o.a.b"), Ok(vec![]));
+    }
+
+    #[test]
+    fn query_quoted_keys() {
+        let value = parse_json(r#"{"a.b": 1, "it's": 2, "[x]": 3}"#).unwrap();
+        assert_eq!(value.query("$['a.b']"), Ok(vec![&Integer(1)]));
+        assert_eq!(value.query(r"$['it\'s']"), Ok(vec![&Integer(2)]));
+        assert_eq!(value.query(r#"$["[x]"]"#), Ok(vec![&Integer(3)]));
+        assert_eq!(value.query("$.a"), Ok(vec![]));
+    }
+
+    #[test]
+    fn query_invalid() {
+        let value = Null;
+        for (path, position) in [
+            ("", 0),
+            ("object", 0),
+            ("$.", 2),
+            ("$..a", 2),
+            ("$[", 1),
+            ("$[x]", 1),
+            ("$['a'", 1),
+            ("$['a'x]", 1),
+            ("$[*", 1),
+            ("$a", 1),
+        ] {
+            let error = value.query(path).unwrap_err();
+            assert_eq!(error.kind(), &ParseJSONErrorKind::InvalidQuery, "{}", path);
+            assert_eq!(error.position(), position, "{}", path);
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::diff::push_segment;
+use crate::value::raw_number_value;
+use crate::JSONValue;
+
+impl JSONValue {
+    /// Checks the value against a small subset of JSON Schema: `type`, as a
+    /// name or an array of names, `required`, `properties` and `items`.
+    /// Other keywords, and ones that aren't the right shape, are ignored.
+    /// Every problem found is given as a message naming the JSON Pointer of
+    /// the value it's about
+    pub fn validate(&self, schema: &JSONValue) -> Result<(), Vec<String>> {
+        let mut errors = vec![];
+        self.validate_into(schema, &mut String::new(), &mut errors);
+        return if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        };
+    }
+
+    fn validate_into(&self, schema: &JSONValue, path: &mut String, errors: &mut Vec<String>) {
+        let type_names = match schema.get("type") {
+            Some(JSONValue::String(name)) => vec![name.as_str()],
+            Some(JSONValue::Array(names)) => names.iter().filter_map(JSONValue::as_str).collect(),
+            _ => vec![],
+        };
+        if !type_names.is_empty() && !type_names.iter().any(|name| self.has_type(name)) {
+            errors.push(format!(
+                "value at {:?} should be {}, not {}",
+                path,
+                type_names.join(" or "),
+                self.value_type()
+            ));
+            return;
+        }
+
+        if let JSONValue::Object(_) = self {
+            for key in schema
+                .get("required")
+                .into_iter()
+                .flat_map(JSONValue::elements)
+            {
+                if let Some(key) = key.as_str().filter(|key| self.get(key).is_none()) {
+                    errors.push(format!(
+                        "object at {:?} is missing required key {:?}",
+                        path, key
+                    ));
+                }
+            }
+
+            if let Some(properties) = schema.get("properties") {
+                for (key, property_schema) in properties.entries() {
+                    if let Some(value) = self.get(key) {
+                        let path_len = push_segment(path, key);
+                        value.validate_into(property_schema, path, errors);
+                        path.truncate(path_len);
+                    }
+                }
+            }
+        }
+
+        if let (JSONValue::Array(items), Some(item_schema)) = (self, schema.get("items")) {
+            for (index, item) in items.iter().enumerate() {
+                let path_len = push_segment(path, &index.to_string());
+                item.validate_into(item_schema, path, errors);
+                path.truncate(path_len);
+            }
+        }
+    }
+
+    /// Whether the value is of the JSON Schema type `name`, where an
+    /// `integer` is any number with no fraction
+    fn has_type(&self, name: &str) -> bool {
+        return match (name, self) {
+            ("integer", JSONValue::Integer(_)) => true,
+            ("integer", JSONValue::Number(number)) => number.is_finite() && number.fract() == 0.0,
+            ("integer", JSONValue::RawNumber(text)) => raw_number_value(text).has_type(name),
+            (name, value) => value.value_type().to_string() == name,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+
+    fn schema(text: &str) -> JSONValue {
+        return parse_json(text).unwrap();
+    }
+
+    const MESSAGE_SCHEMA: &str = r#"{
+        "type": "object",
+        "required": ["message", "success"],
+        "properties": {
+            "message": {"type": "string"},
+            "success": {"type": "boolean"}
+        }
+    }"#;
+
+    #[test]
+    fn validate_simple_object() {
+        let value = parse_json(fixtures::SIMPLE_OBJECT).unwrap();
+        assert_eq!(value.validate(&schema(MESSAGE_SCHEMA)), Ok(()));
+
+        let invalid = parse_json(r#"{"message": 404}"#).unwrap();
+        assert_eq!(
+            invalid.validate(&schema(MESSAGE_SCHEMA)),
+            Err(vec![
+                r#"object at "" is missing required key "success""#.to_string(),
+                r#"value at "/message" should be string, not number"#.to_string(),
+            ])
+        );
+        assert_eq!(
+            parse_json("[]").unwrap().validate(&schema(MESSAGE_SCHEMA)),
+            Err(vec![
+                r#"value at "" should be object, not array"#.to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn validate_items_and_types() {
+        let list_schema =
+            schema(r#"{"type": "array", "items": {"type": ["integer", "null"], "ignored": true}}"#);
+        assert_eq!(
+            parse_json("[1, 2.0, null]").unwrap().validate(&list_schema),
+            Ok(())
+        );
+        assert_eq!(
+            parse_json(r#"[1, 2.5, "x"]"#)
+                .unwrap()
+                .validate(&list_schema),
+            Err(vec![
+                r#"value at "/1" should be integer or null, not number"#.to_string(),
+                r#"value at "/2" should be integer or null, not string"#.to_string(),
+            ])
+        );
+
+        let kitchen_sink = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let nested = schema(
+            r#"{"properties": {"object": {"required": ["items"], "properties": {"items": {"items": {"type": ["object", "boolean", "string", "array"]}}}}}}"#,
+        );
+        assert_eq!(kitchen_sink.validate(&nested), Ok(()));
+        assert_eq!(kitchen_sink.validate(&schema("{}")), Ok(()));
+    }
+}
@@ -0,0 +1,261 @@
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+
+use crate::value::raw_number_value;
+use crate::JSONValue;
+
+/// Maps objects to serde maps, keeping member order, arrays to sequences,
+/// `Integer` to an `i64`, `Number` to an `f64` and `Null` to a unit. A
+/// `RawNumber` is serialized as the `Integer` or `Number` it stands for
+impl Serialize for JSONValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return match self {
+            JSONValue::String(string) => serializer.serialize_str(string),
+            JSONValue::Number(number) => serializer.serialize_f64(*number),
+            JSONValue::Integer(integer) => serializer.serialize_i64(*integer),
+            JSONValue::RawNumber(text) => raw_number_value(text).serialize(serializer),
+            JSONValue::Object(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key.as_str(), value)?;
+                }
+                map.end()
+            }
+            JSONValue::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            JSONValue::True => serializer.serialize_bool(true),
+            JSONValue::False => serializer.serialize_bool(false),
+            JSONValue::Null => serializer.serialize_unit(),
+        };
+    }
+}
+
+/// Accepts anything self-describing. Integers that fit in an `i64` become an
+/// `Integer` and other numbers a `Number`, while `None` and unit become `Null`
+impl<'de> Deserialize<'de> for JSONValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        return deserializer.deserialize_any(JSONValueVisitor);
+    }
+}
+
+struct JSONValueVisitor;
+
+impl<'de> Visitor<'de> for JSONValueVisitor {
+    type Value = JSONValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.write_str("any JSON value");
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<JSONValue, E> {
+        return Ok(JSONValue::from(value));
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<JSONValue, E> {
+        return Ok(JSONValue::Integer(value));
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<JSONValue, E> {
+        return Ok(match i64::try_from(value) {
+            Ok(integer) => JSONValue::Integer(integer),
+            Err(_) => JSONValue::Number(value as f64),
+        });
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<JSONValue, E> {
+        return Ok(JSONValue::Number(value));
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<JSONValue, E> {
+        return Ok(JSONValue::from(value));
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<JSONValue, E> {
+        return Ok(JSONValue::String(value.into()));
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<JSONValue, E> {
+        return Ok(JSONValue::Null);
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<JSONValue, E> {
+        return Ok(JSONValue::Null);
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<JSONValue, D::Error> {
+        return JSONValue::deserialize(deserializer);
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JSONValue, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        return Ok(JSONValue::Array(items));
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JSONValue, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((key, value)) = map.next_entry::<String, JSONValue>()? {
+            entries.push((key.into(), value));
+        }
+        return Ok(JSONValue::Object(entries));
+    }
+}
+
+/// Keeps object members in order only when serde_json's `preserve_order`
+/// feature is on, since its maps are sorted by key otherwise. Maps can't hold
+/// a key twice, so the last member with a repeated key wins. A `Number` that
+/// isn't finite becomes `Null`, as it does when serializing. A `RawNumber`
+/// is parsed by serde_json, so it's only exact with its
+/// `arbitrary_precision` feature
+impl From<JSONValue> for serde_json::Value {
+    fn from(value: JSONValue) -> Self {
+        return match value {
+            JSONValue::String(string) => serde_json::Value::String(string.into_string()),
+            JSONValue::Number(number) => serde_json::Number::from_f64(number)
+                .map_or(serde_json::Value::Null, serde_json::Value::Number),
+            JSONValue::Integer(integer) => serde_json::Value::Number(integer.into()),
+            JSONValue::RawNumber(text) => match text.parse() {
+                Ok(number) => serde_json::Value::Number(number),
+                Err(_) => raw_number_value(&text).into(),
+            },
+            JSONValue::Object(entries) => serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.into_string(), value.into()))
+                    .collect(),
+            ),
+            JSONValue::Array(items) => {
+                serde_json::Value::Array(items.into_iter().map(Into::into).collect())
+            }
+            JSONValue::True => serde_json::Value::Bool(true),
+            JSONValue::False => serde_json::Value::Bool(false),
+            JSONValue::Null => serde_json::Value::Null,
+        };
+    }
+}
+
+/// Numbers serde_json holds as integers become an `Integer` when they fit in
+/// an `i64`, and a `Number` otherwise
+impl From<serde_json::Value> for JSONValue {
+    fn from(value: serde_json::Value) -> Self {
+        return match value {
+            serde_json::Value::Null => JSONValue::Null,
+            serde_json::Value::Bool(boolean) => JSONValue::from(boolean),
+            serde_json::Value::Number(number) => match number.as_i64() {
+                Some(integer) => JSONValue::Integer(integer),
+                None => JSONValue::Number(number.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_json::Value::String(string) => JSONValue::String(string.into()),
+            serde_json::Value::Array(items) => {
+                JSONValue::Array(items.into_iter().map(Into::into).collect())
+            }
+            serde_json::Value::Object(entries) => JSONValue::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.into(), value.into()))
+                    .collect(),
+            ),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fixtures, parse_json, JSONValue};
+
+    #[test]
+    fn serialize_kitchen_sink_round_trip() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let serialized = serde_json::to_string(&value).unwrap();
+        assert_eq!(parse_json(&serialized), Ok(value));
+    }
+
+    #[test]
+    fn deserialize_kitchen_sink() {
+        assert_eq!(
+            serde_json::from_str::<JSONValue>(fixtures::KITCHEN_SINK).ok(),
+            parse_json(fixtures::KITCHEN_SINK).ok()
+        );
+    }
+
+    #[test]
+    fn deserialize_numbers() {
+        assert_eq!(
+            serde_json::from_str::<JSONValue>("[1, -1, 1.5, 18446744073709551615]").ok(),
+            Some(JSONValue::Array(vec![
+                JSONValue::Integer(1),
+                JSONValue::Integer(-1),
+                JSONValue::Number(1.5),
+                JSONValue::Number(18446744073709551615.0)
+            ]))
+        );
+    }
+
+    #[test]
+    fn serialize_round_trip_through_serde() {
+        let value = parse_json(r#"{"b": [true, null, "x"], "a": {"n": -2.5e-3}}"#).unwrap();
+        let serialized = serde_json::to_string(&value).unwrap();
+        assert_eq!(
+            serde_json::from_str::<JSONValue>(&serialized).ok(),
+            Some(value)
+        );
+    }
+
+    #[test]
+    fn serde_json_value_from_kitchen_sink() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(fixtures::KITCHEN_SINK).unwrap();
+        assert_eq!(serde_json::Value::from(value), expected);
+    }
+
+    #[test]
+    fn json_value_from_serde_json_kitchen_sink() {
+        let value: serde_json::Value = serde_json::from_str(fixtures::KITCHEN_SINK).unwrap();
+        let converted = JSONValue::from(value);
+        assert!(converted.semantic_eq(&parse_json(fixtures::KITCHEN_SINK).unwrap()));
+    }
+
+    #[test]
+    fn serde_json_value_numbers() {
+        assert_eq!(
+            serde_json::Value::from(JSONValue::Integer(-3)),
+            serde_json::json!(-3)
+        );
+        assert_eq!(
+            serde_json::Value::from(JSONValue::Number(f64::NAN)),
+            serde_json::Value::Null
+        );
+        assert_eq!(
+            JSONValue::from(serde_json::json!([1, 1.0, u64::MAX])),
+            JSONValue::Array(vec![
+                JSONValue::Integer(1),
+                JSONValue::Number(1.0),
+                JSONValue::Number(u64::MAX as f64)
+            ])
+        );
+    }
+
+    #[test]
+    fn raw_numbers_through_serde() {
+        let raw = |text: &str| JSONValue::RawNumber(text.to_string());
+        assert_eq!(
+            serde_json::Value::from(raw("18446744073709551615")),
+            serde_json::json!(u64::MAX)
+        );
+        assert_eq!(
+            serde_json::Value::from(raw("1e1000")),
+            serde_json::Value::Null
+        );
+        assert_eq!(serde_json::to_string(&raw("2.50")).unwrap(), "2.5");
+        assert_eq!(serde_json::to_string(&raw("-7")).unwrap(), "-7");
+    }
+}
@@ -0,0 +1,795 @@
+use std::fmt::{self, Write};
+use std::io;
+
+use crate::value::raw_number_value;
+use crate::JSONValue;
+
+/// Controls how `to_pretty_string_with` lays out its output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrettyOptions {
+    /// How many spaces each nesting level is indented by
+    pub indent: usize,
+    /// Whether arrays holding only scalars, like `[1, 2, 3]`, are written
+    /// on one line. Arrays holding any array or object are still spread
+    /// over several
+    pub inline_scalar_arrays: bool,
+    /// What ends each line
+    pub newline: NewlineStyle,
+    /// Whether the output ends with a newline, like text files usually do
+    pub trailing_newline: bool,
+    /// How a `Number` with a whole value is written
+    pub float_style: FloatStyle,
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        return PrettyOptions {
+            indent: 2,
+            inline_scalar_arrays: false,
+            newline: NewlineStyle::Lf,
+            trailing_newline: false,
+            float_style: FloatStyle::AlwaysDecimal,
+        };
+    }
+}
+
+/// Controls how `to_string_with` writes compact JSON
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerializeOptions {
+    /// How a `Number` with a whole value is written
+    pub float_style: FloatStyle,
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        return SerializeOptions {
+            float_style: FloatStyle::AlwaysDecimal,
+        };
+    }
+}
+
+/// How a `Number` with a whole value is written. An `Integer` never has a
+/// decimal point, and numbers written with an exponent never get one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatStyle {
+    /// As few chars as possible, so `5.0` is written as `5`, like an
+    /// `Integer` would be
+    Shortest,
+    /// With a decimal point, so `5.0` is written as `5.0` and parses back
+    /// as a `Number`
+    AlwaysDecimal,
+}
+
+/// The line ending pretty-printed output uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NewlineStyle {
+    /// `\n`, as on Unix
+    Lf,
+    /// `\r\n`, as on Windows
+    CrLf,
+}
+
+impl NewlineStyle {
+    pub fn as_str(&self) -> &'static str {
+        return match self {
+            NewlineStyle::Lf => "\n",
+            NewlineStyle::CrLf => "\r\n",
+        };
+    }
+}
+
+/// Serializes to compact JSON, with no whitespace between tokens and
+/// whole `Number`s written with a decimal point
+impl fmt::Display for JSONValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write_compact(f, self, FloatStyle::AlwaysDecimal);
+    }
+}
+
+impl JSONValue {
+    /// Serializes to compact JSON like `to_string`, writing numbers as
+    /// `options` says
+    pub fn to_string_with(&self, options: &SerializeOptions) -> String {
+        let mut output = String::new();
+        write_compact(&mut output, self, options.float_style)
+            .expect("writing to a String can't fail");
+        return output;
+    }
+
+    /// Serializes to multi-line JSON, indenting each nesting level by
+    /// `indent` spaces. Empty objects and arrays stay on one line as `{}`
+    /// and `[]`
+    pub fn to_pretty_string(&self, indent: usize) -> String {
+        return self.to_pretty_string_with(&PrettyOptions {
+            indent,
+            ..PrettyOptions::default()
+        });
+    }
+
+    /// Serializes to multi-line JSON laid out as `options` says
+    pub fn to_pretty_string_with(&self, options: &PrettyOptions) -> String {
+        let mut output = String::new();
+        write_pretty(&mut output, self, options, 0).expect("writing to a String can't fail");
+        if options.trailing_newline {
+            output.push_str(options.newline.as_str());
+        }
+        return output;
+    }
+
+    /// Serializes to compact JSON in a canonical form, in the spirit of JCS
+    /// (RFC 8785), so equal documents give byte-identical output. Object
+    /// members are sorted by key, comparing UTF-16 code units as JCS does,
+    /// numbers are written the way JavaScript would, and strings only
+    /// escape what they have to
+    pub fn to_canonical_string(&self) -> String {
+        let mut output = String::new();
+        write_canonical(&mut output, self).expect("writing to a String can't fail");
+        return output;
+    }
+
+    /// A hash of the canonical serialization, so documents that only differ
+    /// in key order or whitespace hash the same. It's FNV-1a rather than
+    /// `std`'s hasher, so the hash stays the same across Rust versions and
+    /// can be stored. Numbers are hashed as they're written canonically, so
+    /// `1.0` hashes like `1` and `-0.0` like `0`, while NaN and the
+    /// infinities all hash like `null`. Members with a repeated key are all
+    /// hashed, so dropping one changes the hash
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+        write_canonical(&mut hasher, self).expect("hashing can't fail");
+        return hasher.0;
+    }
+
+    /// How many bytes the compact serialization takes up as UTF-8, found by
+    /// counting them as they'd be written rather than building the string,
+    /// for things like checking a document fits a size limit
+    pub fn serialized_len(&self) -> usize {
+        let mut counter = Utf8Len(0);
+        write!(counter, "{}", self).expect("counting can't fail");
+        return counter.0;
+    }
+
+    /// How many UTF-16 code units the compact serialization takes up, which
+    /// is what JavaScript gives as its `length`. Chars outside the Basic
+    /// Multilingual Plane take two
+    pub fn serialized_utf16_len(&self) -> usize {
+        let mut counter = Utf16Len(0);
+        write!(counter, "{}", self).expect("counting can't fail");
+        return counter.0;
+    }
+
+    /// Writes the compact serialization to `writer` as it's produced,
+    /// without building it up in a `String` first
+    pub fn write_to<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        return write!(writer, "{}", self);
+    }
+
+    /// Serializes to compact JSON as UTF-8 bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = vec![];
+        self.write_to(&mut output)
+            .expect("writing to a Vec can't fail");
+        return output;
+    }
+}
+
+fn write_compact<W: Write>(out: &mut W, value: &JSONValue, float_style: FloatStyle) -> fmt::Result {
+    return match value {
+        JSONValue::String(string) => write_json_string(out, string),
+        JSONValue::Number(number) => write_json_number(out, *number, float_style),
+        JSONValue::Integer(integer) => write!(out, "{}", integer),
+        JSONValue::RawNumber(text) => out.write_str(text),
+        JSONValue::Object(entries) => {
+            out.write_char('{')?;
+            for (index, (key, value)) in entries.iter().enumerate() {
+                if index > 0 {
+                    out.write_char(',')?;
+                }
+                write_json_string(out, key)?;
+                out.write_char(':')?;
+                write_compact(out, value, float_style)?;
+            }
+            out.write_char('}')
+        }
+        JSONValue::Array(items) => {
+            out.write_char('[')?;
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.write_char(',')?;
+                }
+                write_compact(out, item, float_style)?;
+            }
+            out.write_char(']')
+        }
+        JSONValue::True => out.write_str("true"),
+        JSONValue::False => out.write_str("false"),
+        JSONValue::Null => out.write_str("null"),
+    };
+}
+
+/// Writes the value pretty-printed, assuming the current line is already
+/// indented for `depth`
+fn write_pretty<W: Write>(
+    out: &mut W,
+    value: &JSONValue,
+    options: &PrettyOptions,
+    depth: usize,
+) -> fmt::Result {
+    let write_indent =
+        |out: &mut W, depth: usize| write!(out, "{:width$}", "", width = options.indent * depth);
+    let is_container =
+        |value: &JSONValue| matches!(value, JSONValue::Array(_) | JSONValue::Object(_));
+    let newline = options.newline.as_str();
+
+    match value {
+        JSONValue::Object(entries) if !entries.is_empty() => {
+            out.write_char('{')?;
+            out.write_str(newline)?;
+            for (index, (key, value)) in entries.iter().enumerate() {
+                if index > 0 {
+                    out.write_char(',')?;
+                    out.write_str(newline)?;
+                }
+                write_indent(out, depth + 1)?;
+                write_json_string(out, key)?;
+                out.write_str(": ")?;
+                write_pretty(out, value, options, depth + 1)?;
+            }
+            out.write_str(newline)?;
+            write_indent(out, depth)?;
+            out.write_char('}')?;
+        }
+        JSONValue::Array(items)
+            if options.inline_scalar_arrays && !items.iter().any(is_container) =>
+        {
+            out.write_char('[')?;
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.write_str(", ")?;
+                }
+                write_compact(out, item, options.float_style)?;
+            }
+            out.write_char(']')?;
+        }
+        JSONValue::Array(items) if !items.is_empty() => {
+            out.write_char('[')?;
+            out.write_str(newline)?;
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.write_char(',')?;
+                    out.write_str(newline)?;
+                }
+                write_indent(out, depth + 1)?;
+                write_pretty(out, item, options, depth + 1)?;
+            }
+            out.write_str(newline)?;
+            write_indent(out, depth)?;
+            out.write_char(']')?;
+        }
+        value => write_compact(out, value, options.float_style)?,
+    }
+
+    return Ok(());
+}
+
+fn write_canonical<W: Write>(out: &mut W, value: &JSONValue) -> fmt::Result {
+    match value {
+        JSONValue::Object(entries) => {
+            let mut sorted = entries.iter().collect::<Vec<_>>();
+            sorted.sort_by(|(key, _), (other_key, _)| {
+                key.encode_utf16().cmp(other_key.encode_utf16())
+            });
+
+            out.write_char('{')?;
+            for (index, (key, value)) in sorted.into_iter().enumerate() {
+                if index > 0 {
+                    out.write_char(',')?;
+                }
+                write_json_string(out, key)?;
+                out.write_char(':')?;
+                write_canonical(out, value)?;
+            }
+            out.write_char('}')?;
+        }
+        JSONValue::Array(items) => {
+            out.write_char('[')?;
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.write_char(',')?;
+                }
+                write_canonical(out, item)?;
+            }
+            out.write_char(']')?;
+        }
+        JSONValue::Number(number) => write_canonical_number(out, *number)?,
+        JSONValue::RawNumber(text) => write_canonical(out, &raw_number_value(text))?,
+        value => write!(out, "{}", value)?,
+    }
+
+    return Ok(());
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Hashes whatever's written to it with 64-bit FNV-1a
+struct Fnv1a(u64);
+
+impl Write for Fnv1a {
+    fn write_str(&mut self, string: &str) -> fmt::Result {
+        for &byte in string.as_bytes() {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(FNV_PRIME);
+        }
+        return Ok(());
+    }
+}
+
+/// Counts the UTF-8 length of whatever's written to it, without keeping it
+struct Utf8Len(usize);
+
+impl Write for Utf8Len {
+    fn write_str(&mut self, string: &str) -> fmt::Result {
+        self.0 += string.len();
+        return Ok(());
+    }
+}
+
+/// Counts the UTF-16 length of whatever's written to it, without keeping it
+struct Utf16Len(usize);
+
+impl Write for Utf16Len {
+    fn write_str(&mut self, string: &str) -> fmt::Result {
+        self.0 += string.chars().map(char::len_utf16).sum::<usize>();
+        return Ok(());
+    }
+}
+
+/// Writes the number the way JavaScript's `Number.prototype.toString` does,
+/// so integral values have no fraction, -0 is `0`, and exponents have an
+/// explicit sign. NaN and the infinities become `null`, as elsewhere
+fn write_canonical_number<W: Write>(out: &mut W, number: f64) -> fmt::Result {
+    if !number.is_finite() {
+        return out.write_str("null");
+    }
+    if number == 0.0 {
+        return out.write_char('0');
+    }
+
+    let magnitude = number.abs();
+    if (1e-6..1e21).contains(&magnitude) {
+        return write!(out, "{}", number);
+    }
+
+    let formatted = format!("{:e}", number);
+    return match formatted.split_once('e') {
+        Some((mantissa, exponent)) if !exponent.starts_with('-') => {
+            write!(out, "{}e+{}", mantissa, exponent)
+        }
+        _ => out.write_str(&formatted),
+    };
+}
+
+/// Wraps `string` in quotes as a JSON string literal, escaping what has to
+/// be escaped the same way serializing does
+pub fn escape_json_string(string: &str) -> String {
+    let mut output = String::with_capacity(string.len() + 2);
+    write_json_string(&mut output, string).expect("writing to a String can't fail");
+    return output;
+}
+
+/// Writes the string wrapped in quotes, escaping quotes, backslashes and
+/// control characters
+pub(crate) fn write_json_string<W: Write>(out: &mut W, string: &str) -> fmt::Result {
+    out.write_char('"')?;
+
+    // Unescaped runs are written in one go rather than char by char
+    let mut run_start = 0;
+    for (index, ch) in string.char_indices() {
+        if ch != '"' && ch != '\\' && ch >= ' ' {
+            continue;
+        }
+
+        out.write_str(&string[run_start..index])?;
+        match ch {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            '\u{08}' => out.write_str("\\b")?,
+            '\u{0C}' => out.write_str("\\f")?,
+            _ => write!(out, "\\u{:04x}", ch as u32)?,
+        }
+        run_start = index + ch.len_utf8();
+    }
+    out.write_str(&string[run_start..])?;
+
+    return out.write_char('"');
+}
+
+/// Writes the number in its shortest round-trippable form, always with a
+/// fraction or exponent so it reads back as a float rather than an
+/// `Integer`. Very large and very small magnitudes use an exponent instead
+/// of a long run of zeros, and NaN and the infinities, which JSON can't
+/// represent, become `null`
+fn write_json_number<W: Write>(out: &mut W, number: f64, float_style: FloatStyle) -> fmt::Result {
+    if !number.is_finite() {
+        return out.write_str("null");
+    }
+
+    let magnitude = number.abs();
+    if magnitude != 0.0 && !(1e-6..1e21).contains(&magnitude) {
+        return write!(out, "{:e}", number);
+    }
+
+    if number.fract() == 0.0 && float_style == FloatStyle::AlwaysDecimal {
+        return write!(out, "{}.0", number);
+    }
+
+    return write!(out, "{}", number);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+    use JSONValue::*;
+
+    #[test]
+    fn display_scalars() {
+        assert_eq!(True.to_string(), "true");
+        assert_eq!(False.to_string(), "false");
+        assert_eq!(Null.to_string(), "null");
+        assert_eq!(String("hey!".into()).to_string(), r#""hey!""#);
+    }
+
+    #[test]
+    fn display_numbers() {
+        assert_eq!(Number(1.0).to_string(), "1.0");
+        assert_eq!(Number(-0.0).to_string(), "-0.0");
+        assert_eq!(Number(-1.5).to_string(), "-1.5");
+        assert_eq!(Number(20000000000.0).to_string(), "20000000000.0");
+        assert_eq!(Number(1e21).to_string(), "1e21");
+        assert_eq!(Number(1.5e-7).to_string(), "1.5e-7");
+    }
+
+    #[test]
+    fn display_integers() {
+        assert_eq!(Integer(1).to_string(), "1");
+        assert_eq!(Integer(-42).to_string(), "-42");
+        assert_eq!(Integer(i64::MIN).to_string(), "-9223372036854775808");
+    }
+
+    #[test]
+    fn display_non_finite_numbers_as_null() {
+        assert_eq!(Number(f64::NAN).to_string(), "null");
+        assert_eq!(Number(f64::INFINITY).to_string(), "null");
+        assert_eq!(Number(f64::NEG_INFINITY).to_string(), "null");
+    }
+
+    #[test]
+    fn display_string_escapes() {
+        assert_eq!(
+            String("a\"b\\c\nd\te\u{01}f\u{1F}".into()).to_string(),
+            r#""a\"b\\c\nd\te\u0001f\u001f""#
+        );
+    }
+
+    #[test]
+    fn display_empty_containers() {
+        assert_eq!(Object(vec![]).to_string(), "{}");
+        assert_eq!(Array(vec![]).to_string(), "[]");
+    }
+
+    #[test]
+    fn display_nested_array() {
+        assert_eq!(
+            Array(vec![Integer(1), Array(vec![Number(2.0), Array(vec![])])]).to_string(),
+            "[1,[2.0,[]]]"
+        );
+    }
+
+    #[test]
+    fn display_kitchen_sink() {
+        assert_eq!(
+            parse_json(fixtures::KITCHEN_SINK).unwrap().to_string(),
+            r#"{"object":{"thing":1,"another":20000000000.0,"true":false,"exists":null,"items":[{"type":"item thingo"},true,"hey!",[false,true]]}}"#
+        );
+    }
+
+    #[test]
+    fn display_kitchen_sink_round_trip() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        assert_eq!(parse_json(&value.to_string()), Ok(value));
+    }
+
+    const PRETTY_FIXTURE: &str =
+        r#"{"data": {"number": 1, "list": [true, null], "empty": {}}, "none": []}"#;
+
+    #[test]
+    fn pretty_indent_2() {
+        assert_eq!(
+            parse_json(PRETTY_FIXTURE).unwrap().to_pretty_string(2),
+            r#"{
+  "data": {
+    "number": 1,
+    "list": [
+      true,
+      null
+    ],
+    "empty": {}
+  },
+  "none": []
+}"#
+        );
+    }
+
+    #[test]
+    fn pretty_indent_4() {
+        assert_eq!(
+            parse_json(PRETTY_FIXTURE).unwrap().to_pretty_string(4),
+            r#"{
+    "data": {
+        "number": 1,
+        "list": [
+            true,
+            null
+        ],
+        "empty": {}
+    },
+    "none": []
+}"#
+        );
+    }
+
+    fn inline_scalar_arrays() -> PrettyOptions {
+        return PrettyOptions {
+            inline_scalar_arrays: true,
+            ..PrettyOptions::default()
+        };
+    }
+
+    #[test]
+    fn pretty_inline_scalar_arrays() {
+        let value =
+            parse_json(r#"{"a": [1,2,3], "b": [{"c": ["x", null]}, [true]], "d": []}"#).unwrap();
+        assert_eq!(
+            value.to_pretty_string_with(&inline_scalar_arrays()),
+            r#"{
+  "a": [1, 2, 3],
+  "b": [
+    {
+      "c": ["x", null]
+    },
+    [true]
+  ],
+  "d": []
+}"#
+        );
+        assert_eq!(
+            parse_json("[1,2,3]")
+                .unwrap()
+                .to_pretty_string_with(&inline_scalar_arrays()),
+            "[1, 2, 3]"
+        );
+        assert_eq!(
+            parse_json(r#"[{"a": 1}, {"a": 2}]"#)
+                .unwrap()
+                .to_pretty_string_with(&inline_scalar_arrays()),
+            r#"[
+  {
+    "a": 1
+  },
+  {
+    "a": 2
+  }
+]"#
+        );
+        assert_eq!(
+            value.to_pretty_string_with(&PrettyOptions::default()),
+            value.to_pretty_string(2)
+        );
+    }
+
+    #[test]
+    fn pretty_crlf_newlines() {
+        let value = parse_json(r#"{"a": [1, {}], "b": "x\ny"}"#).unwrap();
+        let options = PrettyOptions {
+            newline: NewlineStyle::CrLf,
+            ..PrettyOptions::default()
+        };
+        let pretty = value.to_pretty_string_with(&options);
+        assert_eq!(
+            pretty,
+            "{\r\n  \"a\": [\r\n    1,\r\n    {}\r\n  ],\r\n  \"b\": \"x\\ny\"\r\n}"
+        );
+        assert_eq!(pretty.replace("\r\n", "\n"), value.to_pretty_string(2));
+        assert_eq!(parse_json(&pretty), Ok(value));
+    }
+
+    #[test]
+    fn pretty_trailing_newline() {
+        let value = parse_json("[1]").unwrap();
+        let options = |newline| PrettyOptions {
+            newline,
+            trailing_newline: true,
+            ..PrettyOptions::default()
+        };
+        assert_eq!(
+            value.to_pretty_string_with(&options(NewlineStyle::Lf)),
+            "[\n  1\n]\n"
+        );
+        assert_eq!(
+            value.to_pretty_string_with(&options(NewlineStyle::CrLf)),
+            "[\r\n  1\r\n]\r\n"
+        );
+        assert_eq!(
+            Null.to_pretty_string_with(&options(NewlineStyle::Lf)),
+            "null\n"
+        );
+    }
+
+    #[test]
+    fn float_styles() {
+        let shortest = SerializeOptions {
+            float_style: FloatStyle::Shortest,
+        };
+        assert_eq!(Number(5.0).to_string_with(&shortest), "5");
+        assert_eq!(Number(-0.0).to_string_with(&shortest), "-0");
+        assert_eq!(Number(2.5).to_string_with(&shortest), "2.5");
+        assert_eq!(Number(1e300).to_string_with(&shortest), "1e300");
+
+        let always_decimal = SerializeOptions {
+            float_style: FloatStyle::AlwaysDecimal,
+        };
+        assert_eq!(Number(5.0).to_string_with(&always_decimal), "5.0");
+        assert_eq!(Number(2.5).to_string_with(&always_decimal), "2.5");
+        assert_eq!(
+            Number(5.0).to_string_with(&SerializeOptions::default()),
+            Number(5.0).to_string()
+        );
+        assert_eq!(Integer(5).to_string_with(&always_decimal), "5");
+    }
+
+    #[test]
+    fn float_styles_nested_and_pretty() {
+        let value = parse_json(r#"{"a": [5.0, 5], "b": 0.0}"#).unwrap();
+        let shortest = SerializeOptions {
+            float_style: FloatStyle::Shortest,
+        };
+        assert_eq!(value.to_string_with(&shortest), r#"{"a":[5,5],"b":0}"#);
+        assert_eq!(value.to_string(), r#"{"a":[5.0,5],"b":0.0}"#);
+
+        let options = PrettyOptions {
+            float_style: FloatStyle::Shortest,
+            inline_scalar_arrays: true,
+            ..PrettyOptions::default()
+        };
+        assert_eq!(
+            value.to_pretty_string_with(&options),
+            "{\n  \"a\": [5, 5],\n  \"b\": 0\n}"
+        );
+    }
+
+    #[test]
+    fn serialized_len_kitchen_sink() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        assert_eq!(value.serialized_len(), value.to_string().len());
+        assert_eq!(
+            value.serialized_utf16_len(),
+            value.to_string().encode_utf16().count()
+        );
+    }
+
+    #[test]
+    fn serialized_len_non_ascii() {
+        let value = parse_json(r#"{"é": ["😀", "a\nb", 1.0]}"#).unwrap();
+        let serialized = value.to_string();
+        assert_eq!(value.serialized_len(), serialized.len());
+        assert_eq!(value.serialized_len(), 26);
+        assert_eq!(
+            value.serialized_utf16_len(),
+            serialized.encode_utf16().count()
+        );
+        assert_eq!(value.serialized_utf16_len(), 23);
+        assert_eq!(Null.serialized_len(), 4);
+    }
+
+    #[test]
+    fn pretty_scalar() {
+        assert_eq!(String("hey!".into()).to_pretty_string(2), r#""hey!""#);
+    }
+
+    #[test]
+    fn pretty_kitchen_sink_round_trip() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        assert_eq!(parse_json(&value.to_pretty_string(4)), Ok(value));
+    }
+
+    #[test]
+    fn write_to_kitchen_sink() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let mut output: Vec<u8> = vec![];
+        value.write_to(&mut output).unwrap();
+        assert_eq!(output, value.to_string().into_bytes());
+        assert_eq!(parse_json(std::str::from_utf8(&output).unwrap()), Ok(value));
+    }
+
+    #[test]
+    fn to_bytes_matches_display() {
+        let value = parse_json(r#"{"name": "wörld", "list": [1, 2.5, null]}"#).unwrap();
+        assert_eq!(value.to_bytes(), value.to_string().into_bytes());
+    }
+
+    #[test]
+    fn canonical_reordered_objects_match() {
+        let value = parse_json(r#"{"b": 1, "a": {"y": [true, null], "x": "s"}}"#).unwrap();
+        let reordered = parse_json(r#"{ "a": { "x": "s", "y": [true, null] }, "b": 1 }"#).unwrap();
+        assert_eq!(
+            value.to_canonical_string(),
+            r#"{"a":{"x":"s","y":[true,null]},"b":1}"#
+        );
+        assert_eq!(value.to_canonical_string(), reordered.to_canonical_string());
+    }
+
+    #[test]
+    fn canonical_numbers() {
+        let value = Array(vec![
+            Number(2.0e10),
+            Number(-0.0),
+            Number(1.5),
+            Number(1e21),
+            Number(1.5e-7),
+            Integer(-3),
+            Number(f64::NAN),
+        ]);
+        assert_eq!(
+            value.to_canonical_string(),
+            "[20000000000,0,1.5,1e+21,1.5e-7,-3,null]"
+        );
+    }
+
+    #[test]
+    fn canonical_key_order_uses_utf16() {
+        // U+1F600 is encoded as surrogates, which sort before U+FB33 in UTF-16
+        // even though it's the larger code point
+        let value = Object(vec![
+            ("\u{FB33}".into(), Integer(1)),
+            ("\u{1F600}".into(), Integer(2)),
+            ("a".into(), Integer(3)),
+        ]);
+        assert_eq!(
+            value.to_canonical_string(),
+            "{\"a\":3,\"\u{1F600}\":2,\"\u{FB33}\":1}"
+        );
+    }
+
+    #[test]
+    fn content_hash_ignores_key_order_and_whitespace() {
+        let value = parse_json(r#"{"b": 1, "a": {"y": [true, null], "x": "s"}}"#).unwrap();
+        let reordered =
+            parse_json(r#"{ "a": { "x": "s", "y": [true, null] }, "b": 1.0 }"#).unwrap();
+        assert_eq!(value.content_hash(), reordered.content_hash());
+
+        // FNV-1a of the empty object's canonical form, `{}`
+        assert_eq!(Object(vec![]).content_hash(), 0x08f44b07b5901a25);
+        assert_eq!(Number(f64::NAN).content_hash(), Null.content_hash());
+    }
+
+    #[test]
+    fn content_hash_differs_for_different_documents() {
+        let hashes = [
+            r#"{"a": 1}"#,
+            r#"{"a": 2}"#,
+            r#"{"b": 1}"#,
+            r#"{"a": "1"}"#,
+            r#"[1, 2]"#,
+            r#"[2, 1]"#,
+            r#"{"a": 1, "a": 1}"#,
+        ]
+        .map(|input| parse_json(input).unwrap().content_hash());
+        for (index, hash) in hashes.iter().enumerate() {
+            assert!(!hashes[index + 1..].contains(hash), "{}", index);
+        }
+    }
+}
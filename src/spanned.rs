@@ -0,0 +1,200 @@
+use std::borrow::Cow;
+
+use crate::value::parse_array_index;
+use crate::{parse_document, Build, JSONParseResult, JSONValue, ParseOptions};
+
+/// A range of byte offsets into the parsed input, with `end` being just past
+/// the last byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Something parsed along with where it was in the input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Span,
+}
+
+/// The same as [`JSONValue`], except every object key and nested value has
+/// its span
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpannedValue {
+    String(String),
+    Number(f64),
+    Integer(i64),
+    RawNumber(String),
+    Object(Vec<(Spanned<String>, Spanned<SpannedValue>)>),
+    Array(Vec<Spanned<SpannedValue>>),
+    True,
+    False,
+    Null,
+}
+
+/// Parses a single JSON document like `parse_json`, keeping the span of every
+/// value and key for tools that need to point back into the source
+pub fn parse_json_spanned(string: &str) -> JSONParseResult<Spanned<SpannedValue>> {
+    return parse_document::<BuildSpanned>(string, &ParseOptions::default());
+}
+
+impl Spanned<SpannedValue> {
+    /// Looks up the value at a JSON Pointer, the same way as
+    /// `JSONValue::pointer`
+    pub fn pointer(&self, path: &str) -> Option<&Spanned<SpannedValue>> {
+        if path.is_empty() {
+            return Some(self);
+        }
+
+        let mut spanned = self;
+        for segment in path.strip_prefix('/')?.split('/') {
+            let segment = segment.replace("~1", "/").replace("~0", "~");
+            spanned = match &spanned.value {
+                SpannedValue::Object(members) => members
+                    .iter()
+                    .find(|(key, _)| key.value == segment)
+                    .map(|(_, value)| value)?,
+                SpannedValue::Array(items) => items.get(parse_array_index(&segment)?)?,
+                _ => return None,
+            };
+        }
+
+        return Some(spanned);
+    }
+
+    /// Drops the spans, leaving a plain value
+    pub fn into_value(self) -> JSONValue {
+        return match self.value {
+            SpannedValue::String(string) => JSONValue::String(string.into()),
+            SpannedValue::Number(number) => JSONValue::Number(number),
+            SpannedValue::Integer(integer) => JSONValue::Integer(integer),
+            SpannedValue::RawNumber(text) => JSONValue::RawNumber(text),
+            SpannedValue::Object(members) => JSONValue::Object(
+                members
+                    .into_iter()
+                    .map(|(key, value)| (key.value.into(), value.into_value()))
+                    .collect(),
+            ),
+            SpannedValue::Array(items) => {
+                JSONValue::Array(items.into_iter().map(Spanned::into_value).collect())
+            }
+            SpannedValue::True => JSONValue::True,
+            SpannedValue::False => JSONValue::False,
+            SpannedValue::Null => JSONValue::Null,
+        };
+    }
+}
+
+struct BuildSpanned;
+
+impl<'a> Build<'a> for BuildSpanned {
+    type Key = Spanned<String>;
+    type Value = Spanned<SpannedValue>;
+
+    fn key(key: Cow<'a, str>, span: Span) -> Spanned<String> {
+        return Spanned {
+            value: key.into_owned(),
+            span,
+        };
+    }
+
+    fn scalar(value: JSONValue, span: Span) -> Spanned<SpannedValue> {
+        let value = match value {
+            JSONValue::String(string) => SpannedValue::String(string.into_string()),
+            JSONValue::Number(number) => SpannedValue::Number(number),
+            JSONValue::Integer(integer) => SpannedValue::Integer(integer),
+            JSONValue::RawNumber(text) => SpannedValue::RawNumber(text),
+            JSONValue::True => SpannedValue::True,
+            JSONValue::False => SpannedValue::False,
+            JSONValue::Null => SpannedValue::Null,
+            JSONValue::Object(_) | JSONValue::Array(_) => {
+                unreachable!("arrays and objects aren't scalars")
+            }
+        };
+        return Spanned { value, span };
+    }
+
+    fn array(items: Vec<Spanned<SpannedValue>>, span: Span) -> Spanned<SpannedValue> {
+        return Spanned {
+            value: SpannedValue::Array(items),
+            span,
+        };
+    }
+
+    fn object(
+        members: Vec<(Spanned<String>, Spanned<SpannedValue>)>,
+        span: Span,
+    ) -> Spanned<SpannedValue> {
+        return Spanned {
+            value: SpannedValue::Object(members),
+            span,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+
+    fn source_of<'a>(input: &'a str, spanned: &Spanned<SpannedValue>) -> &'a str {
+        return &input[spanned.span.start..spanned.span.end];
+    }
+
+    #[test]
+    fn spanned_nested_value_in_kitchen_sink() {
+        let input = fixtures::KITCHEN_SINK;
+        let spanned = parse_json_spanned(input).unwrap();
+
+        let item = spanned.pointer("/object/items/0").unwrap();
+        assert_eq!(
+            source_of(input, item),
+            r#"{
+                "type": "item thingo"
+            }"#
+        );
+
+        let nested = spanned.pointer("/object/items/3/1").unwrap();
+        assert_eq!(nested.value, SpannedValue::True);
+        assert_eq!(source_of(input, nested), "true");
+        assert_eq!(
+            source_of(input, spanned.pointer("/object/another").unwrap()),
+            "2.0e10"
+        );
+    }
+
+    #[test]
+    fn spanned_whole_document_and_keys() {
+        let input = r#"  {"a": [1, "two"]}  "#;
+        let spanned = parse_json_spanned(input).unwrap();
+        assert_eq!(spanned.span, Span { start: 2, end: 19 });
+
+        match &spanned.value {
+            SpannedValue::Object(members) => {
+                assert_eq!(members[0].0.value, "a");
+                assert_eq!(members[0].0.span, Span { start: 3, end: 6 });
+                assert_eq!(members[0].1.span, Span { start: 8, end: 18 });
+            }
+            other => panic!("expected an object, got {:?}", other),
+        }
+        assert_eq!(
+            source_of(input, spanned.pointer("/a/1").unwrap()),
+            r#""two""#
+        );
+    }
+
+    #[test]
+    fn spanned_into_value_matches_parse_json() {
+        let spanned = parse_json_spanned(fixtures::KITCHEN_SINK).unwrap();
+        assert_eq!(Ok(spanned.into_value()), parse_json(fixtures::KITCHEN_SINK));
+    }
+
+    #[test]
+    fn spanned_errors_like_parse_json() {
+        assert_eq!(
+            parse_json_spanned("[1,,]").unwrap_err(),
+            parse_json("[1,,]").unwrap_err()
+        );
+    }
+}
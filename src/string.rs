@@ -0,0 +1,333 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::rc::Rc;
+
+use crate::JSONValue;
+
+/// An object key or string value, which is either owned or shares its
+/// allocation with other strings holding the same text. A parsed document's
+/// strings are owned unless they're interned. Either way it behaves like a
+/// `str`, and compares and hashes by its contents alone.
+///
+/// Sharing uses `Rc`, so neither this nor `JSONValue` is `Send` or `Sync`
+#[derive(Clone)]
+pub enum JSONString {
+    Owned(String),
+    Shared(Rc<str>),
+}
+
+impl JSONString {
+    pub fn as_str(&self) -> &str {
+        return match self {
+            JSONString::Owned(string) => string,
+            JSONString::Shared(string) => string,
+        };
+    }
+
+    /// Whether both share one allocation, rather than just holding equal
+    /// text
+    pub fn ptr_eq(&self, other: &JSONString) -> bool {
+        return match (self, other) {
+            (JSONString::Shared(string), JSONString::Shared(other)) => Rc::ptr_eq(string, other),
+            _ => false,
+        };
+    }
+
+    pub fn into_string(self) -> String {
+        return match self {
+            JSONString::Owned(string) => string,
+            JSONString::Shared(string) => string.to_string(),
+        };
+    }
+}
+
+impl Deref for JSONString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        return self.as_str();
+    }
+}
+
+impl AsRef<str> for JSONString {
+    fn as_ref(&self) -> &str {
+        return self.as_str();
+    }
+}
+
+impl Borrow<str> for JSONString {
+    fn borrow(&self) -> &str {
+        return self.as_str();
+    }
+}
+
+impl From<String> for JSONString {
+    fn from(string: String) -> Self {
+        return JSONString::Owned(string);
+    }
+}
+
+impl From<&str> for JSONString {
+    fn from(string: &str) -> Self {
+        return JSONString::Owned(string.to_string());
+    }
+}
+
+impl From<Rc<str>> for JSONString {
+    fn from(string: Rc<str>) -> Self {
+        return JSONString::Shared(string);
+    }
+}
+
+impl From<JSONString> for String {
+    fn from(string: JSONString) -> Self {
+        return string.into_string();
+    }
+}
+
+impl PartialEq for JSONString {
+    fn eq(&self, other: &JSONString) -> bool {
+        return self.as_str() == other.as_str();
+    }
+}
+
+impl Eq for JSONString {}
+
+impl PartialEq<str> for JSONString {
+    fn eq(&self, other: &str) -> bool {
+        return self.as_str() == other;
+    }
+}
+
+impl PartialEq<&str> for JSONString {
+    fn eq(&self, other: &&str) -> bool {
+        return self.as_str() == *other;
+    }
+}
+
+impl PartialEq<String> for JSONString {
+    fn eq(&self, other: &String) -> bool {
+        return self.as_str() == other;
+    }
+}
+
+impl PartialOrd for JSONString {
+    fn partial_cmp(&self, other: &JSONString) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for JSONString {
+    fn cmp(&self, other: &JSONString) -> Ordering {
+        return self.as_str().cmp(other.as_str());
+    }
+}
+
+impl Hash for JSONString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+/// Prints like a `str`, whether it's shared or not
+impl fmt::Debug for JSONString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return fmt::Debug::fmt(self.as_str(), f);
+    }
+}
+
+impl fmt::Display for JSONString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.write_str(self.as_str());
+    }
+}
+
+impl JSONValue {
+    /// Makes every object key in the tree share one allocation with the
+    /// other keys holding the same text, which saves memory when the same
+    /// keys repeat a lot, like in an array of similar objects. Each distinct
+    /// key still gets its own allocation, so documents with few repeats get
+    /// slightly bigger, and every key is hashed once to find its match
+    pub fn intern_keys(&mut self) {
+        self.intern_keys_with(&mut HashSet::new());
+    }
+
+    /// A copy of the tree where every object key and string value shares
+    /// one allocation with the others holding the same text, keys and
+    /// values alike, like `intern_keys` and the `intern_strings` parse
+    /// option together. The copy equals the original
+    pub fn deduplicated(&self) -> JSONValue {
+        return self.deduplicated_with(&mut HashSet::new());
+    }
+
+    fn deduplicated_with(&self, interned: &mut HashSet<Rc<str>>) -> JSONValue {
+        return match self {
+            JSONValue::String(string) => {
+                JSONValue::String(JSONString::Shared(intern(interned, string)))
+            }
+            JSONValue::Object(members) => JSONValue::Object(
+                members
+                    .iter()
+                    .map(|(key, value)| {
+                        let key = JSONString::Shared(intern(interned, key));
+                        return (key, value.deduplicated_with(interned));
+                    })
+                    .collect(),
+            ),
+            JSONValue::Array(items) => JSONValue::Array(
+                items
+                    .iter()
+                    .map(|item| item.deduplicated_with(interned))
+                    .collect(),
+            ),
+            scalar => scalar.clone(),
+        };
+    }
+
+    fn intern_keys_with(&mut self, interned: &mut HashSet<Rc<str>>) {
+        match self {
+            JSONValue::Object(members) => {
+                for (key, value) in members {
+                    *key = JSONString::Shared(intern(interned, key));
+                    value.intern_keys_with(interned);
+                }
+            }
+            JSONValue::Array(items) => {
+                for item in items {
+                    item.intern_keys_with(interned);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The allocation in `interned` holding `text`, adding one if there isn't
+/// one yet
+pub(crate) fn intern(interned: &mut HashSet<Rc<str>>, text: &str) -> Rc<str> {
+    if let Some(shared) = interned.get(text) {
+        return Rc::clone(shared);
+    }
+    let shared = Rc::<str>::from(text);
+    interned.insert(Rc::clone(&shared));
+    return shared;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_json;
+
+    #[test]
+    fn owned_and_shared_compare_by_contents() {
+        let owned = JSONString::from("key");
+        let shared = JSONString::from(Rc::<str>::from("key"));
+        assert_eq!(owned, shared);
+        assert_eq!(owned.cmp(&shared), Ordering::Equal);
+        assert_eq!(shared, "key");
+        assert_eq!(format!("{:?}", shared), r#""key""#);
+    }
+
+    #[test]
+    fn ptr_eq_only_for_one_allocation() {
+        let shared = JSONString::from(Rc::<str>::from("key"));
+        assert!(shared.ptr_eq(&shared.clone()));
+        assert!(!shared.ptr_eq(&JSONString::from(Rc::<str>::from("key"))));
+        assert!(!JSONString::from("key").ptr_eq(&JSONString::from("key")));
+    }
+
+    fn keys_of(value: &JSONValue) -> Vec<&JSONString> {
+        return value
+            .elements()
+            .flat_map(|item| match item {
+                JSONValue::Object(members) => members.iter().map(|(key, _)| key).collect(),
+                _ => vec![],
+            })
+            .collect();
+    }
+
+    #[test]
+    fn intern_keys_shares_repeated_keys() {
+        let mut value = parse_json(
+            r#"[{"id": 1, "name": "a"}, {"id": 2, "name": "b"}, {"name": "c", "id": 3}]"#,
+        )
+        .unwrap();
+        let before = value.clone();
+        value.intern_keys();
+        assert_eq!(value, before);
+
+        let keys = keys_of(&value);
+        assert!(keys[0].ptr_eq(keys[2]));
+        assert!(keys[0].ptr_eq(keys[5]));
+        assert!(keys[1].ptr_eq(keys[3]));
+        assert!(keys[1].ptr_eq(keys[4]));
+        assert!(!keys[0].ptr_eq(keys[1]));
+    }
+
+    #[test]
+    fn intern_keys_nested_objects() {
+        let mut value = parse_json(r#"{"a": {"a": {"b": 1}}, "b": 2}"#).unwrap();
+        value.intern_keys();
+        let inner = value.get("a").unwrap();
+        match (&value, inner) {
+            (JSONValue::Object(outer), JSONValue::Object(inner)) => {
+                assert!(outer[0].0.ptr_eq(&inner[0].0));
+            }
+            _ => panic!("expected objects"),
+        }
+        assert_eq!(value.pointer("/a/a/b"), Some(&JSONValue::Integer(1)));
+    }
+
+    #[test]
+    fn deduplicated_shares_keys_and_values() {
+        let value = parse_json(
+            r#"[{"kind": "user", "name": "a"}, {"kind": "user", "name": "user"}, "kind"]"#,
+        )
+        .unwrap();
+        let deduplicated = value.deduplicated();
+        assert_eq!(deduplicated, value);
+
+        let strings = |value: &JSONValue| -> Vec<JSONString> {
+            let mut strings = vec![];
+            for item in value.elements() {
+                match item {
+                    JSONValue::Object(members) => {
+                        for (key, value) in members {
+                            strings.push(key.clone());
+                            if let JSONValue::String(string) = value {
+                                strings.push(string.clone());
+                            }
+                        }
+                    }
+                    JSONValue::String(string) => strings.push(string.clone()),
+                    _ => {}
+                }
+            }
+            return strings;
+        };
+        // kind, user, name, a, kind, user, name, user, kind
+        let shared = strings(&deduplicated);
+        assert!(shared[0].ptr_eq(&shared[4]));
+        assert!(shared[0].ptr_eq(&shared[8]));
+        assert!(shared[1].ptr_eq(&shared[5]));
+        assert!(shared[1].ptr_eq(&shared[7]));
+        assert!(shared[2].ptr_eq(&shared[6]));
+        assert!(!shared[0].ptr_eq(&shared[1]));
+
+        let distinct = shared
+            .iter()
+            .filter_map(|string| match string {
+                JSONString::Shared(shared) => Some(Rc::as_ptr(shared)),
+                JSONString::Owned(_) => None,
+            })
+            .collect::<HashSet<_>>();
+        assert_eq!(distinct.len(), 4);
+        assert!(strings(&value)
+            .iter()
+            .all(|string| matches!(string, JSONString::Owned(_))));
+    }
+}
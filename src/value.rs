@@ -0,0 +1,1733 @@
+use crate::{JSONString, JSONValue, ParseJSONError, ParseJSONErrorKind};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// Which kind of value a [`JSONValue`] is, with `Integer` and `Number`
+/// both being a `Number`, and `True` and `False` both a `Bool`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    String,
+    Number,
+    Object,
+    Array,
+    Bool,
+    Null,
+}
+
+/// Writes the type's name as JSON calls it, like "string"
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ValueType::String => "string",
+            ValueType::Number => "number",
+            ValueType::Object => "object",
+            ValueType::Array => "array",
+            ValueType::Bool => "boolean",
+            ValueType::Null => "null",
+        };
+        return f.write_str(name);
+    }
+}
+
+/// What `JSONValue::merge_with` does when both sides of a merge are arrays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayStrategy {
+    /// Use the other array in place of this one, as `merge` does
+    Replace,
+    /// Add the other array's items to the end of this one
+    Concat,
+}
+
+impl JSONValue {
+    /// An `Object` with no members, to add to as a document is built up
+    pub fn empty_object() -> JSONValue {
+        return JSONValue::Object(vec![]);
+    }
+
+    /// An `Array` with no items, to add to as a document is built up
+    pub fn empty_array() -> JSONValue {
+        return JSONValue::Array(vec![]);
+    }
+
+    pub fn value_type(&self) -> ValueType {
+        return match self {
+            JSONValue::String(_) => ValueType::String,
+            JSONValue::Number(_) | JSONValue::Integer(_) | JSONValue::RawNumber(_) => {
+                ValueType::Number
+            }
+            JSONValue::Object(_) => ValueType::Object,
+            JSONValue::Array(_) => ValueType::Array,
+            JSONValue::True | JSONValue::False => ValueType::Bool,
+            JSONValue::Null => ValueType::Null,
+        };
+    }
+
+    /// Returns the contents of a `String`, or `None` for any other variant
+    pub fn as_str(&self) -> Option<&str> {
+        return match self {
+            JSONValue::String(string) => Some(string),
+            _ => None,
+        };
+    }
+
+    /// Returns the value of a `Number`, or of an `Integer` or `RawNumber`
+    /// converted to the nearest `f64`, or `None` for any other variant
+    pub fn as_f64(&self) -> Option<f64> {
+        return match self {
+            JSONValue::Number(number) => Some(*number),
+            JSONValue::Integer(integer) => Some(*integer as f64),
+            JSONValue::RawNumber(text) => text.parse().ok(),
+            _ => None,
+        };
+    }
+
+    /// Returns the value of an `Integer`, or of a `RawNumber` written as an
+    /// integer that fits, or `None` for any other variant, including a
+    /// `Number` that happens to be integral
+    pub fn as_i64(&self) -> Option<i64> {
+        return match self {
+            JSONValue::Integer(integer) => Some(*integer),
+            JSONValue::RawNumber(text) => text.parse().ok(),
+            _ => None,
+        };
+    }
+
+    /// Returns `true` for `True` and `false` for `False`, or `None` for any
+    /// other variant
+    pub fn as_bool(&self) -> Option<bool> {
+        return match self {
+            JSONValue::True => Some(true),
+            JSONValue::False => Some(false),
+            _ => None,
+        };
+    }
+
+    pub fn is_null(&self) -> bool {
+        return matches!(self, JSONValue::Null);
+    }
+
+    /// Returns the members of an `Object`, or `None` for any other variant.
+    /// Keys that were interned are copied into their own `String`s
+    pub fn into_object(self) -> Option<Vec<(String, JSONValue)>> {
+        return match self {
+            JSONValue::Object(members) => Some(
+                members
+                    .into_iter()
+                    .map(|(key, value)| (key.into_string(), value))
+                    .collect(),
+            ),
+            _ => None,
+        };
+    }
+
+    /// Returns the items of an `Array`, or `None` for any other variant
+    pub fn into_array(self) -> Option<Vec<JSONValue>> {
+        return match self {
+            JSONValue::Array(items) => Some(items),
+            _ => None,
+        };
+    }
+
+    /// Returns the contents of a `String`, or `None` for any other variant
+    pub fn into_string(self) -> Option<String> {
+        return match self {
+            JSONValue::String(string) => Some(string.into_string()),
+            _ => None,
+        };
+    }
+
+    /// Returns the number as `as_f64` does, or `None` for any other variant
+    pub fn into_f64(self) -> Option<f64> {
+        return self.as_f64();
+    }
+
+    /// Looks up `key` when this is an `Object`, returning `None` for any
+    /// other variant or when the key is absent. Objects can hold the same
+    /// key more than once, in which case the first entry wins
+    pub fn get(&self, key: &str) -> Option<&JSONValue> {
+        return match self {
+            JSONValue::Object(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        };
+    }
+
+    /// Like `get`, but for changing the value in place
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut JSONValue> {
+        return match self {
+            JSONValue::Object(entries) => entries
+                .iter_mut()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        };
+    }
+
+    /// The item at `index` when this is an `Array`, for changing it in
+    /// place. Returns `None` for any other variant or when the index is out
+    /// of bounds
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut JSONValue> {
+        return match self {
+            JSONValue::Array(items) => items.get_mut(index),
+            _ => None,
+        };
+    }
+
+    /// Like `get`, but keys match when they only differ in ASCII case, so
+    /// `"Message"` finds `"message"`. When several keys differ only in case,
+    /// like `"id"` and `"ID"`, whichever comes first is found, which may not
+    /// be the one that matches exactly
+    pub fn get_ci(&self, key: &str) -> Option<&JSONValue> {
+        return match self {
+            JSONValue::Object(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key.eq_ignore_ascii_case(key))
+                .map(|(_, value)| value),
+            _ => None,
+        };
+    }
+
+    /// Removes the member with `key` from an `Object` and returns its value,
+    /// keeping the other members in order. Only the first member is removed
+    /// when the key appears more than once, the same one `get` finds.
+    /// Returns `None` for any other variant or when the key is absent
+    pub fn remove(&mut self, key: &str) -> Option<JSONValue> {
+        return match self {
+            JSONValue::Object(entries) => {
+                let index = entries.iter().position(|(entry_key, _)| entry_key == key)?;
+                Some(entries.remove(index).1)
+            }
+            _ => None,
+        };
+    }
+
+    /// Returns this value, leaving `Null`, the default, in its place
+    pub fn take(&mut self) -> JSONValue {
+        return std::mem::replace(self, JSONValue::Null);
+    }
+
+    /// Looks up the value at a JSON Pointer (RFC 6901) such as
+    /// `/object/items/0`, where each segment is an object key or an array
+    /// index, and `~1` and `~0` in a segment stand for `/` and `~`. The empty
+    /// pointer is the whole value. Returns `None` when any segment is missing
+    pub fn pointer(&self, path: &str) -> Option<&JSONValue> {
+        if path.is_empty() {
+            return Some(self);
+        }
+
+        let mut value = self;
+        for segment in path.strip_prefix('/')?.split('/') {
+            let segment = segment.replace("~1", "/").replace("~0", "~");
+            value = match value {
+                JSONValue::Object(_) => value.get(&segment)?,
+                JSONValue::Array(items) => items.get(parse_array_index(&segment)?)?,
+                _ => return None,
+            };
+        }
+
+        return Some(value);
+    }
+
+    /// Sets the value at a JSON Pointer, replacing what's there or adding it
+    /// as a new member or item. Missing objects and arrays along the way are
+    /// made, with an array for a segment that's an index or `-` and an object
+    /// otherwise. In an array, `-` or the index one past the end appends. The
+    /// empty pointer replaces the whole value. Fails when the path goes
+    /// through a scalar, uses a key on an array or skips past an array's end,
+    /// with the error's position being the byte offset into `path`, and
+    /// leaves the value unchanged
+    pub fn pointer_set(&mut self, path: &str, value: JSONValue) -> Result<(), ParseJSONError> {
+        if path.is_empty() {
+            *self = value;
+            return Ok(());
+        }
+        let segments = pointer_segments(path)?;
+        let conflict = |position: usize| {
+            return ParseJSONError::new(
+                ParseJSONErrorKind::PathConflict,
+                path.as_bytes(),
+                position,
+            );
+        };
+        if let Some(offset) = self.pointer_set_conflict(&segments) {
+            return Err(conflict(offset));
+        }
+
+        let mut target = self;
+        let mut value = Some(value);
+        for (index, (offset, segment)) in segments.iter().enumerate() {
+            // What to put here when nothing is yet: the value itself at the
+            // end of the path, or a container for the next segment
+            let is_leaf = index + 1 == segments.len();
+            let mut new_value = || match segments.get(index + 1) {
+                Some((_, next)) if next == "-" || parse_array_index(next).is_some() => {
+                    JSONValue::Array(vec![])
+                }
+                Some(_) => JSONValue::Object(vec![]),
+                None => value.take().unwrap(),
+            };
+
+            target = match target {
+                JSONValue::Object(members) => {
+                    match members.iter().position(|(key, _)| key == segment) {
+                        Some(position) if is_leaf => {
+                            members[position].1 = new_value();
+                            return Ok(());
+                        }
+                        Some(position) => &mut members[position].1,
+                        None => {
+                            members.push((segment.as_str().into(), new_value()));
+                            &mut members.last_mut().unwrap().1
+                        }
+                    }
+                }
+                JSONValue::Array(items) => {
+                    let position = match segment.as_str() {
+                        "-" => Some(items.len()),
+                        segment => parse_array_index(segment),
+                    };
+                    match position {
+                        Some(position) if position == items.len() => {
+                            items.push(new_value());
+                            items.last_mut().unwrap()
+                        }
+                        Some(position) if position < items.len() && is_leaf => {
+                            items[position] = new_value();
+                            return Ok(());
+                        }
+                        Some(position) if position < items.len() => &mut items[position],
+                        _ => return Err(conflict(*offset)),
+                    }
+                }
+                _ => return Err(conflict(*offset)),
+            };
+        }
+
+        return Ok(());
+    }
+
+    /// Walks the path `pointer_set` would without changing anything, and
+    /// returns the offset of the first segment it would fail at, so nothing
+    /// is made along the way when it fails
+    fn pointer_set_conflict(&self, segments: &[(usize, String)]) -> Option<usize> {
+        // `None` once the path leaves what's already there, and goes
+        // through containers that would be made for it
+        let mut target = Some(self);
+        for (index, (offset, segment)) in segments.iter().enumerate() {
+            let is_leaf = index + 1 == segments.len();
+            target = match target {
+                Some(JSONValue::Object(members)) => members
+                    .iter()
+                    .find(|(key, _)| key == segment)
+                    .map(|(_, value)| value)
+                    .filter(|_| !is_leaf),
+                Some(JSONValue::Array(items)) => {
+                    let position = match segment.as_str() {
+                        "-" => Some(items.len()),
+                        segment => parse_array_index(segment),
+                    };
+                    match position {
+                        Some(position) if position < items.len() && !is_leaf => {
+                            Some(&items[position])
+                        }
+                        Some(position) if position <= items.len() => None,
+                        _ => return Some(*offset),
+                    }
+                }
+                Some(_) => return Some(*offset),
+                // A made array is empty, so only appending to it works
+                None => match parse_array_index(segment) {
+                    Some(position) if position != 0 => return Some(*offset),
+                    _ => None,
+                },
+            };
+        }
+        return None;
+    }
+
+    /// Iterates over the members of an `Object` in order, or over nothing
+    /// for any other variant
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &JSONValue)> {
+        let members: &[(JSONString, JSONValue)] = match self {
+            JSONValue::Object(members) => members,
+            _ => &[],
+        };
+        return members.iter().map(|(key, value)| (key.as_str(), value));
+    }
+
+    /// A map from each key of an `Object` to its value, for looking members
+    /// up without caring about their order, or `None` for any other variant.
+    /// The map is sorted by key rather than in member order. A key the
+    /// object has more than once maps to its first member's value, like
+    /// `get` finds, so the map can have fewer entries than the object has
+    /// members
+    pub fn as_map(&self) -> Option<BTreeMap<&str, &JSONValue>> {
+        let JSONValue::Object(members) = self else {
+            return None;
+        };
+
+        let mut map = BTreeMap::new();
+        for (key, value) in members {
+            map.entry(key.as_str()).or_insert(value);
+        }
+        return Some(map);
+    }
+
+    /// Iterates over the items of an `Array`, or over nothing for any other
+    /// variant
+    pub fn elements(&self) -> impl Iterator<Item = &JSONValue> {
+        let items: &[JSONValue] = match self {
+            JSONValue::Array(items) => items,
+            _ => &[],
+        };
+        return items.iter();
+    }
+
+    /// Moves out the members of an `Object` in order, or gives nothing for
+    /// any other variant. Iterating over the value itself moves out the
+    /// items of an `Array` instead
+    pub fn into_entries(self) -> impl Iterator<Item = (String, JSONValue)> {
+        let members = match self {
+            JSONValue::Object(members) => members,
+            _ => vec![],
+        };
+        return members
+            .into_iter()
+            .map(|(key, value)| (key.into_string(), value));
+    }
+
+    /// Compares two values as JSON documents rather than as Rust values, so
+    /// objects with the same members in a different order are equal, and an
+    /// `Integer` equals a `Number` with exactly the same value. Arrays still
+    /// have to be in the same order, and a `RawNumber` is compared as the
+    /// value it would parse to otherwise. A key appearing more than once is
+    /// compared like any other member, so both objects need the same number
+    /// of members, each pairing up with a distinct equal member of the other
+    pub fn semantic_eq(&self, other: &JSONValue) -> bool {
+        return self.semantic_eq_by(other, &numbers_semantic_eq);
+    }
+
+    /// Compares two values like `semantic_eq`, except numbers only have to
+    /// be within `epsilon` of each other, so results of float arithmetic
+    /// like `0.1 + 0.2` match what they should have come to. NaN never
+    /// matches anything, and integers too big to be exact as an `f64` are
+    /// rounded before they're compared
+    pub fn approx_eq(&self, other: &JSONValue, epsilon: f64) -> bool {
+        return self.semantic_eq_by(other, &|value, other_value| {
+            if numbers_semantic_eq(value, other_value) {
+                return true;
+            }
+            return match (value.as_f64(), other_value.as_f64()) {
+                (Some(number), Some(other_number)) => (number - other_number).abs() <= epsilon,
+                _ => false,
+            };
+        });
+    }
+
+    /// Compares two values like `semantic_eq`, with `numbers_eq` deciding
+    /// whether two numbers match
+    fn semantic_eq_by(
+        &self,
+        other: &JSONValue,
+        numbers_eq: &dyn Fn(&JSONValue, &JSONValue) -> bool,
+    ) -> bool {
+        return match (self, other) {
+            (JSONValue::Object(members), JSONValue::Object(other_members)) => {
+                if members.len() != other_members.len() {
+                    return false;
+                }
+
+                let mut matched = vec![false; other_members.len()];
+                members.iter().all(|(key, value)| {
+                    let found = other_members.iter().enumerate().position(
+                        |(index, (other_key, other_value))| {
+                            !matched[index]
+                                && key == other_key
+                                && value.semantic_eq_by(other_value, numbers_eq)
+                        },
+                    );
+                    if let Some(index) = found {
+                        matched[index] = true;
+                    }
+                    found.is_some()
+                })
+            }
+            (JSONValue::Array(items), JSONValue::Array(other_items)) => {
+                items.len() == other_items.len()
+                    && items
+                        .iter()
+                        .zip(other_items)
+                        .all(|(item, other_item)| item.semantic_eq_by(other_item, numbers_eq))
+            }
+            _ if self.value_type() == ValueType::Number
+                && other.value_type() == ValueType::Number =>
+            {
+                numbers_eq(self, other)
+            }
+            _ => self == other,
+        };
+    }
+
+    /// Every key of every object in the tree, at any depth, including
+    /// objects inside arrays
+    pub fn all_keys(&self) -> BTreeSet<String> {
+        let mut keys = BTreeSet::new();
+        self.collect_keys(&mut keys);
+        return keys;
+    }
+
+    fn collect_keys(&self, keys: &mut BTreeSet<String>) {
+        match self {
+            JSONValue::Object(members) => {
+                for (key, value) in members {
+                    keys.insert(key.to_string());
+                    value.collect_keys(keys);
+                }
+            }
+            JSONValue::Array(items) => {
+                for item in items {
+                    item.collect_keys(keys);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// How many values the tree holds, counting this one, each array and
+    /// object, and everything inside them. Object keys aren't values, so
+    /// they don't count
+    pub fn node_count(&self) -> usize {
+        return 1 + match self {
+            JSONValue::Object(members) => members.iter().map(|(_, value)| value.node_count()).sum(),
+            JSONValue::Array(items) => items.iter().map(JSONValue::node_count).sum(),
+            _ => 0,
+        };
+    }
+
+    /// How deeply arrays and objects nest, with a scalar being 0 and an
+    /// array or object holding only scalars, or nothing, being 1
+    pub fn depth(&self) -> usize {
+        let children_depth = match self {
+            JSONValue::Object(members) => members.iter().map(|(_, value)| value.depth()).max(),
+            JSONValue::Array(items) => items.iter().map(JSONValue::depth).max(),
+            _ => return 0,
+        };
+        return 1 + children_depth.unwrap_or(0);
+    }
+
+    /// A copy for previews that goes at most `max_depth` levels deep, the
+    /// way `depth` counts them, so the copy's `depth` is never more than
+    /// `max_depth`. Each array or object that would be deeper is replaced by
+    /// the string `"…"`, whatever it holds. Scalars are kept wherever their
+    /// container is
+    pub fn truncate_depth(&self, max_depth: usize) -> JSONValue {
+        if max_depth == 0 && matches!(self, JSONValue::Array(_) | JSONValue::Object(_)) {
+            return JSONValue::String("…".into());
+        }
+        return match self {
+            JSONValue::Object(members) => JSONValue::Object(
+                members
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.truncate_depth(max_depth - 1)))
+                    .collect(),
+            ),
+            JSONValue::Array(items) => JSONValue::Array(
+                items
+                    .iter()
+                    .map(|item| item.truncate_depth(max_depth - 1))
+                    .collect(),
+            ),
+            scalar => scalar.clone(),
+        };
+    }
+
+    /// Every value in the tree that `predicate` holds for, including this
+    /// one and arrays and objects as well as scalars. Values come in the
+    /// order they'd be written out, so a container comes before what it holds
+    pub fn find_all<F: Fn(&JSONValue) -> bool>(&self, predicate: F) -> Vec<&JSONValue> {
+        let mut found = vec![];
+        self.find_all_into(&predicate, &mut found);
+        return found;
+    }
+
+    fn find_all_into<'a, F: Fn(&JSONValue) -> bool>(
+        &'a self,
+        predicate: &F,
+        found: &mut Vec<&'a JSONValue>,
+    ) {
+        if predicate(self) {
+            found.push(self);
+        }
+        match self {
+            JSONValue::Object(members) => {
+                for (_, value) in members {
+                    value.find_all_into(predicate, found);
+                }
+            }
+            JSONValue::Array(items) => {
+                for item in items {
+                    item.find_all_into(predicate, found);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Replaces every value in the tree that `replace` gives a new value for,
+    /// including this one, trying each container before what it holds. A
+    /// replacement isn't looked inside, so what `replace` gives back is kept
+    /// as it is and can't be replaced again
+    pub fn replace_all<F: Fn(&JSONValue) -> Option<JSONValue>>(&mut self, replace: F) {
+        self.replace_all_with(&replace);
+    }
+
+    fn replace_all_with<F: Fn(&JSONValue) -> Option<JSONValue>>(&mut self, replace: &F) {
+        if let Some(replacement) = replace(self) {
+            *self = replacement;
+            return;
+        }
+        match self {
+            JSONValue::Object(members) => {
+                for (_, value) in members {
+                    value.replace_all_with(replace);
+                }
+            }
+            JSONValue::Array(items) => {
+                for item in items {
+                    item.replace_all_with(replace);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Merges `other` into this value. When both are objects, each member of
+    /// `other` is merged into the member with the same key, or added at the
+    /// end if there isn't one, so nested objects are merged recursively.
+    /// Anything else, including arrays, replaces this value wholesale rather
+    /// than being combined with it. Like `get`, a key this object has more
+    /// than once is merged into its first member
+    pub fn merge(&mut self, other: JSONValue) {
+        self.merge_with(other, ArrayStrategy::Replace);
+    }
+
+    /// Like `merge`, but with `array_strategy` saying what happens when
+    /// both sides are arrays, at any depth. Arrays are never merged item by
+    /// item, so with `Concat` objects inside them aren't combined either
+    pub fn merge_with(&mut self, other: JSONValue, array_strategy: ArrayStrategy) {
+        match (self, other) {
+            (JSONValue::Object(members), JSONValue::Object(other_members)) => {
+                for (key, value) in other_members {
+                    match members
+                        .iter_mut()
+                        .find(|(member_key, _)| *member_key == key)
+                    {
+                        Some((_, member_value)) => member_value.merge_with(value, array_strategy),
+                        None => members.push((key, value)),
+                    }
+                }
+            }
+            (JSONValue::Array(items), JSONValue::Array(other_items))
+                if array_strategy == ArrayStrategy::Concat =>
+            {
+                items.extend(other_items);
+            }
+            (this, other) => *this = other,
+        }
+    }
+
+    /// Sorts the members of every object in the tree by key, comparing
+    /// them as strings, including objects inside arrays. The sort is stable,
+    /// so members with the same key keep their order
+    pub fn sort_keys(&mut self) {
+        match self {
+            JSONValue::Object(members) => {
+                members.sort_by(|(key, _), (other_key, _)| key.cmp(other_key));
+                for (_, value) in members {
+                    value.sort_keys();
+                }
+            }
+            JSONValue::Array(items) => {
+                for item in items {
+                    item.sort_keys();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Turns every `Number` in the tree with a whole value that fits in an
+    /// `i64`, like `5.0`, into an `Integer`, so it's written as `5` rather
+    /// than `5.0`. Numbers with a fraction, ones too big for an `i64` and
+    /// `-0.0` are left alone, since an `Integer` can't hold them exactly
+    pub fn normalize_numbers(&mut self) {
+        match self {
+            JSONValue::Number(number) if !(*number == 0.0 && number.is_sign_negative()) => {
+                if let Some(integer) = integral_value(*number) {
+                    *self = JSONValue::Integer(integer);
+                }
+            }
+            JSONValue::Object(members) => {
+                for (_, value) in members {
+                    value.normalize_numbers();
+                }
+            }
+            JSONValue::Array(items) => {
+                for item in items {
+                    item.normalize_numbers();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Replaces the value of every object member whose key is in `keys`
+    /// with the string `"***"`, at any depth, including objects inside
+    /// arrays. Keys must match exactly
+    pub fn redact(&mut self, keys: &[&str]) {
+        self.redact_with(keys, false);
+    }
+
+    /// Like `redact`, but with `ignore_case` keys also match when they only
+    /// differ in ASCII case, so `"password"` catches `"Password"` too
+    pub fn redact_with(&mut self, keys: &[&str], ignore_case: bool) {
+        match self {
+            JSONValue::Object(members) => {
+                for (key, value) in members {
+                    let matches = keys.iter().any(|redacted| {
+                        if ignore_case {
+                            key.eq_ignore_ascii_case(redacted)
+                        } else {
+                            key == redacted
+                        }
+                    });
+                    if matches {
+                        *value = JSONValue::String("***".into());
+                    } else {
+                        value.redact_with(keys, ignore_case);
+                    }
+                }
+            }
+            JSONValue::Array(items) => {
+                for item in items {
+                    item.redact_with(keys, ignore_case);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Moves out the items of an `Array`, or gives nothing for any other variant,
+/// like `elements`. An `Object`'s members can be moved out with
+/// `into_entries`
+impl IntoIterator for JSONValue {
+    type Item = JSONValue;
+    type IntoIter = std::vec::IntoIter<JSONValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let items = match self {
+            JSONValue::Array(items) => items,
+            _ => vec![],
+        };
+        return items.into_iter();
+    }
+}
+
+/// `Null`, which is also what `take` leaves behind
+impl Default for JSONValue {
+    fn default() -> Self {
+        return JSONValue::Null;
+    }
+}
+
+/// Values equal themselves unless they hold a NaN `Number`, which never
+/// equals anything. A value holding one can go in a `HashSet`, but that
+/// value can't be found again
+impl Eq for JSONValue {}
+
+/// Hashes agree with both `==` and `semantic_eq`, so objects hash the same
+/// whatever order their members are in, and a `Number` with a whole value
+/// hashes like the `Integer` it equals. Other numbers hash by their bits,
+/// with `-0.0` hashing like `0.0`, and a `RawNumber` hashes like the value
+/// it would parse to otherwise
+impl Hash for JSONValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            JSONValue::String(string) => {
+                state.write_u8(0);
+                string.hash(state);
+            }
+            JSONValue::Number(number) => {
+                state.write_u8(1);
+                match integral_value(*number) {
+                    Some(integer) => state.write_i64(integer),
+                    None => state.write_u64(number.to_bits()),
+                }
+            }
+            JSONValue::Integer(integer) => {
+                state.write_u8(1);
+                state.write_i64(*integer);
+            }
+            JSONValue::RawNumber(text) => raw_number_value(text).hash(state),
+            JSONValue::Object(members) => {
+                state.write_u8(2);
+                state.write_usize(members.len());
+                // Members are hashed on their own and summed, so their order
+                // doesn't change the result
+                let sum = members.iter().fold(0u64, |sum, member| {
+                    let mut hasher = DefaultHasher::new();
+                    member.hash(&mut hasher);
+                    return sum.wrapping_add(hasher.finish());
+                });
+                state.write_u64(sum);
+            }
+            JSONValue::Array(items) => {
+                state.write_u8(3);
+                items.hash(state);
+            }
+            JSONValue::True => state.write_u8(4),
+            JSONValue::False => state.write_u8(5),
+            JSONValue::Null => state.write_u8(6),
+        }
+    }
+}
+
+/// The `i64` equal to `number`, if it's whole and in range. Both zeroes give
+/// `0`
+fn integral_value(number: f64) -> Option<i64> {
+    let in_range = (i64::MIN as f64..i64::MAX as f64).contains(&number);
+    return (in_range && number.fract() == 0.0).then_some(number as i64);
+}
+
+/// Whether two numbers are equal as `semantic_eq` compares them
+fn numbers_semantic_eq(value: &JSONValue, other: &JSONValue) -> bool {
+    return match (value, other) {
+        (JSONValue::Integer(integer), JSONValue::Number(number))
+        | (JSONValue::Number(number), JSONValue::Integer(integer)) => {
+            // `i64::MAX as f64` rounds up to 2^63, which is out of range
+            let in_range = (i64::MIN as f64..i64::MAX as f64).contains(number);
+            in_range && number.fract() == 0.0 && *number as i64 == *integer
+        }
+        (JSONValue::RawNumber(text), _) => numbers_semantic_eq(&raw_number_value(text), other),
+        (_, JSONValue::RawNumber(text)) => numbers_semantic_eq(value, &raw_number_value(text)),
+        _ => value == other,
+    };
+}
+
+/// The `Integer` or `Number` that a `RawNumber`'s text would have been
+/// parsed to without `preserve_number_text`
+pub(crate) fn raw_number_value(text: &str) -> JSONValue {
+    let is_integer = !text.contains(['.', 'e', 'E']) && text != "-0";
+    if let Some(integer) = text.parse().ok().filter(|_| is_integer) {
+        return JSONValue::Integer(integer);
+    }
+    return JSONValue::Number(text.parse().unwrap_or(f64::NAN));
+}
+
+/// Splits a JSON Pointer into its unescaped segments, each with the byte
+/// offset in `path` it starts at. The empty pointer has no segments
+pub(crate) fn pointer_segments(path: &str) -> Result<Vec<(usize, String)>, ParseJSONError> {
+    if path.is_empty() {
+        return Ok(vec![]);
+    }
+    let Some(rest) = path.strip_prefix('/') else {
+        let kind = ParseJSONErrorKind::InvalidPointer;
+        return Err(ParseJSONError::new(kind, path.as_bytes(), 0));
+    };
+
+    let mut segments = vec![];
+    let mut offset = 1;
+    for segment in rest.split('/') {
+        segments.push((offset, segment.replace("~1", "/").replace("~0", "~")));
+        offset += segment.len() + 1;
+    }
+    return Ok(segments);
+}
+
+/// Parses an array index from a JSON Pointer segment, which has to be plain
+/// digits without leading zeros
+pub(crate) fn parse_array_index(segment: &str) -> Option<usize> {
+    let is_index = !segment.is_empty()
+        && segment.bytes().all(|byte| byte.is_ascii_digit())
+        && (segment == "0" || !segment.starts_with('0'));
+    return if is_index { segment.parse().ok() } else { None };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+    use JSONValue::*;
+
+    fn all_variants() -> Vec<JSONValue> {
+        return vec![
+            String("hey!".into()),
+            Number(1.5),
+            Integer(7),
+            Object(vec![("a".into(), Null)]),
+            Array(vec![True]),
+            True,
+            False,
+            Null,
+        ];
+    }
+
+    #[test]
+    fn as_str_only_matches_strings() {
+        let values = all_variants();
+        let results = values.iter().map(JSONValue::as_str).collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![Some("hey!"), None, None, None, None, None, None, None]
+        );
+    }
+
+    #[test]
+    fn as_f64_only_matches_numbers() {
+        let results = all_variants()
+            .iter()
+            .map(JSONValue::as_f64)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![None, Some(1.5), Some(7.0), None, None, None, None, None]
+        );
+    }
+
+    #[test]
+    fn as_i64_only_matches_integers() {
+        let results = all_variants()
+            .iter()
+            .map(JSONValue::as_i64)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![None, None, Some(7), None, None, None, None, None]
+        );
+    }
+
+    #[test]
+    fn as_bool_only_matches_booleans() {
+        let results = all_variants()
+            .iter()
+            .map(JSONValue::as_bool)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![None, None, None, None, None, Some(true), Some(false), None]
+        );
+    }
+
+    #[test]
+    fn is_null_only_matches_null() {
+        let results = all_variants()
+            .iter()
+            .map(JSONValue::is_null)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![false, false, false, false, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn get_simple_object() {
+        let value = parse_json(fixtures::SIMPLE_OBJECT).unwrap();
+        assert_eq!(
+            value.get("message"),
+            Some(&String("things are broken".into()))
+        );
+        assert_eq!(value.get("success"), Some(&False));
+    }
+
+    #[test]
+    fn get_nested_object() {
+        let value = parse_json(fixtures::NESTED_OBJECT).unwrap();
+        assert_eq!(
+            value.get("data").and_then(|data| data.get("number")),
+            Some(&Integer(1))
+        );
+    }
+
+    #[test]
+    fn get_missing_key() {
+        let value = parse_json(fixtures::SIMPLE_OBJECT).unwrap();
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    fn get_on_non_object() {
+        assert_eq!(Array(vec![True]).get("0"), None);
+        assert_eq!(String("message".into()).get("message"), None);
+    }
+
+    #[test]
+    fn get_duplicate_key_returns_first() {
+        let value = parse_json(r#"{"a": 1, "a": 2}"#).unwrap();
+        assert_eq!(value.get("a"), Some(&Integer(1)));
+    }
+
+    #[test]
+    fn get_mut_nested_edit() {
+        let mut value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        if let Some(thing) = value
+            .get_mut("object")
+            .and_then(|object| object.get_mut("thing"))
+        {
+            *thing = Number(5.0);
+        }
+        *value
+            .get_mut("object")
+            .and_then(|object| object.get_mut("items"))
+            .and_then(|items| items.get_index_mut(3))
+            .and_then(|pair| pair.get_index_mut(0))
+            .unwrap() = Null;
+
+        assert_eq!(value.pointer("/object/thing"), Some(&Number(5.0)));
+        assert_eq!(
+            value.pointer("/object/items/3"),
+            Some(&Array(vec![Null, True]))
+        );
+        assert_eq!(value.get_mut("missing"), None);
+    }
+
+    #[test]
+    fn get_mut_edge_cases() {
+        let mut value = parse_json(r#"{"a": 1, "a": 2}"#).unwrap();
+        *value.get_mut("a").unwrap() = Integer(3);
+        assert_eq!(value, parse_json(r#"{"a": 3, "a": 2}"#).unwrap());
+
+        let mut items = parse_json("[1]").unwrap();
+        assert_eq!(items.get_index_mut(1), None);
+        assert_eq!(items.get_mut("0"), None);
+        assert_eq!(value.get_index_mut(0), None);
+    }
+
+    #[test]
+    fn pointer_deep_lookup() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        assert_eq!(
+            value.pointer("/object/items/0/type"),
+            Some(&String("item thingo".into()))
+        );
+        assert_eq!(value.pointer("/object/items/3/1"), Some(&True));
+        assert_eq!(value.pointer(""), Some(&value));
+    }
+
+    #[test]
+    fn pointer_out_of_bounds_index() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        assert_eq!(value.pointer("/object/items/4"), None);
+        assert_eq!(value.pointer("/object/items/-"), None);
+        assert_eq!(value.pointer("/object/items/01"), None);
+    }
+
+    #[test]
+    fn pointer_missing_or_mismatched() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        assert_eq!(value.pointer("/object/missing"), None);
+        assert_eq!(value.pointer("/object/thing/0"), None);
+        assert_eq!(value.pointer("object"), None);
+    }
+
+    #[test]
+    fn pointer_escaped_segments() {
+        let value = parse_json(r#"{"a/b": {"m~n": 1, "~1": 2}}"#).unwrap();
+        assert_eq!(value.pointer("/a~1b/m~0n"), Some(&Integer(1)));
+        assert_eq!(value.pointer("/a~1b/~01"), Some(&Integer(2)));
+    }
+
+    #[test]
+    fn entries_in_order() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let keys = value
+            .pointer("/object")
+            .unwrap()
+            .entries()
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["thing", "another", "true", "exists", "items"]);
+    }
+
+    #[test]
+    fn elements_of_array() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let items = value.pointer("/object/items/3").unwrap();
+        assert_eq!(items.elements().collect::<Vec<_>>(), vec![&False, &True]);
+    }
+
+    #[test]
+    fn entries_and_elements_on_other_variants() {
+        for value in all_variants() {
+            if !matches!(value, Object(_)) {
+                assert_eq!(value.entries().count(), 0);
+            }
+            if !matches!(value, Array(_)) {
+                assert_eq!(value.elements().count(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn semantic_eq_reordered_objects() {
+        let value = parse_json(r#"{"a": 1, "b": {"c": [true, null], "d": "x"}}"#).unwrap();
+        let reordered = parse_json(r#"{"b": {"d": "x", "c": [true, null]}, "a": 1}"#).unwrap();
+        assert_ne!(value, reordered);
+        assert!(value.semantic_eq(&reordered));
+    }
+
+    #[test]
+    fn semantic_eq_reordered_arrays() {
+        let value = parse_json("[1, 2, 3]").unwrap();
+        assert!(value.semantic_eq(&value));
+        assert!(!value.semantic_eq(&parse_json("[3, 2, 1]").unwrap()));
+        assert!(!value.semantic_eq(&parse_json("[1, 2]").unwrap()));
+    }
+
+    #[test]
+    fn semantic_eq_different_members() {
+        let value = parse_json(r#"{"a": 1, "b": 2}"#).unwrap();
+        assert!(!value.semantic_eq(&parse_json(r#"{"a": 1, "b": 3}"#).unwrap()));
+        assert!(!value.semantic_eq(&parse_json(r#"{"a": 1, "c": 2}"#).unwrap()));
+        assert!(!value.semantic_eq(&parse_json(r#"{"a": 1}"#).unwrap()));
+    }
+
+    #[test]
+    fn semantic_eq_duplicate_keys() {
+        let value = parse_json(r#"{"a": 1, "a": 2}"#).unwrap();
+        assert!(value.semantic_eq(&parse_json(r#"{"a": 2, "a": 1}"#).unwrap()));
+        assert!(!value.semantic_eq(&parse_json(r#"{"a": 1, "a": 1}"#).unwrap()));
+        assert!(!value.semantic_eq(&parse_json(r#"{"a": 2}"#).unwrap()));
+    }
+
+    #[test]
+    fn semantic_eq_numbers() {
+        assert!(Integer(1).semantic_eq(&Number(1.0)));
+        assert!(Number(1.0).semantic_eq(&Integer(1)));
+        assert!(!Integer(1).semantic_eq(&Number(1.5)));
+        assert!(!Integer(i64::MAX).semantic_eq(&Number(i64::MAX as f64)));
+        assert!(!Integer(1).semantic_eq(&String("1".into())));
+    }
+
+    #[test]
+    fn approx_eq_numbers() {
+        assert!(Number(0.1 + 0.2).approx_eq(&Number(0.3), 1e-9));
+        assert!(Number(0.30000000000000004).approx_eq(&Number(0.3), f64::EPSILON));
+        assert!(Number(0.30000000000000004) != Number(0.3));
+        assert!(Integer(3).approx_eq(&Number(3.0000001), 1e-6));
+        assert!(!Integer(3).approx_eq(&Number(3.1), 1e-6));
+        assert!(Number(f64::INFINITY).approx_eq(&Number(f64::INFINITY), 0.0));
+        assert!(!Number(f64::NAN).approx_eq(&Number(f64::NAN), 1.0));
+        assert!(!Integer(1).approx_eq(&String("1".into()), 1.0));
+    }
+
+    #[test]
+    fn approx_eq_containers() {
+        let value = parse_json(r#"{"a": [0.30000000000000004, "x"], "b": {"c": 1.0}}"#).unwrap();
+        let close = parse_json(r#"{"b": {"c": 1}, "a": [0.3, "x"]}"#).unwrap();
+        assert!(value.approx_eq(&close, 1e-12));
+        assert!(!value.approx_eq(
+            &parse_json(r#"{"a": [0.3, "y"], "b": {"c": 1}}"#).unwrap(),
+            1e-12
+        ));
+        assert!(!value.approx_eq(
+            &parse_json(r#"{"a": [0.3], "b": {"c": 1}}"#).unwrap(),
+            1e-12
+        ));
+        assert!(!value.approx_eq(
+            &parse_json(r#"{"a": [0.4, "x"], "b": {"c": 1}}"#).unwrap(),
+            1e-12
+        ));
+    }
+
+    #[test]
+    fn merge_nested_objects() {
+        let mut value = parse_json(
+            r#"{"name": "app", "server": {"port": 80, "tls": {"enabled": false}}, "tags": [1, 2]}"#,
+        )
+        .unwrap();
+        value.merge(
+            parse_json(r#"{"server": {"tls": {"enabled": true}, "host": "x"}, "tags": [3]}"#)
+                .unwrap(),
+        );
+        assert_eq!(
+            Ok(value),
+            parse_json(
+                r#"{"name": "app", "server": {"port": 80, "tls": {"enabled": true}, "host": "x"}, "tags": [3]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn merge_object_overwritten_by_scalar() {
+        let mut value = parse_json(r#"{"a": {"b": 1}}"#).unwrap();
+        value.merge(parse_json(r#"{"a": null}"#).unwrap());
+        assert_eq!(Ok(value), parse_json(r#"{"a": null}"#));
+
+        let mut value = parse_json(r#"{"a": {"b": 1}}"#).unwrap();
+        value.merge(Integer(7));
+        assert_eq!(value, Integer(7));
+    }
+
+    #[test]
+    fn merge_scalar_overwritten_by_object() {
+        let mut value = parse_json(r#"{"a": 1}"#).unwrap();
+        value.merge(parse_json(r#"{"a": {"b": 2}}"#).unwrap());
+        assert_eq!(Ok(value), parse_json(r#"{"a": {"b": 2}}"#));
+    }
+
+    #[test]
+    fn merge_with_array_strategies() {
+        let base = parse_json(r#"{"tags": [1, 2], "nested": {"list": [{"a": 1}], "keep": true}}"#)
+            .unwrap();
+        let other =
+            parse_json(r#"{"tags": [3], "nested": {"list": [{"a": 2}]}, "new": []}"#).unwrap();
+
+        let mut replaced = base.clone();
+        replaced.merge_with(other.clone(), ArrayStrategy::Replace);
+        let mut merged = base.clone();
+        merged.merge(other.clone());
+        assert_eq!(replaced, merged);
+        assert_eq!(
+            Ok(replaced),
+            parse_json(r#"{"tags": [3], "nested": {"list": [{"a": 2}], "keep": true}, "new": []}"#)
+        );
+
+        let mut concatenated = base;
+        concatenated.merge_with(other, ArrayStrategy::Concat);
+        assert_eq!(
+            Ok(concatenated),
+            parse_json(
+                r#"{"tags": [1, 2, 3], "nested": {"list": [{"a": 1}, {"a": 2}], "keep": true}, "new": []}"#
+            )
+        );
+    }
+
+    #[test]
+    fn merge_with_concat_only_joins_two_arrays() {
+        let mut value = parse_json(r#"{"a": [1], "b": 2}"#).unwrap();
+        value.merge_with(
+            parse_json(r#"{"a": {"x": 1}, "b": [2]}"#).unwrap(),
+            ArrayStrategy::Concat,
+        );
+        assert_eq!(Ok(value), parse_json(r#"{"a": {"x": 1}, "b": [2]}"#));
+
+        let mut value = Array(vec![Null]);
+        value.merge_with(Array(vec![True]), ArrayStrategy::Concat);
+        assert_eq!(value, Array(vec![Null, True]));
+    }
+
+    #[test]
+    fn value_type_of_each_variant() {
+        let types = all_variants()
+            .iter()
+            .map(JSONValue::value_type)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                ValueType::String,
+                ValueType::Number,
+                ValueType::Number,
+                ValueType::Object,
+                ValueType::Array,
+                ValueType::Bool,
+                ValueType::Bool,
+                ValueType::Null
+            ]
+        );
+    }
+
+    #[test]
+    fn value_type_display() {
+        assert_eq!(ValueType::Bool.to_string(), "boolean");
+        assert_eq!(Array(vec![]).value_type().to_string(), "array");
+    }
+
+    #[test]
+    fn all_keys_kitchen_sink() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let expected = [
+            "another", "exists", "items", "object", "thing", "true", "type",
+        ]
+        .iter()
+        .map(|key| key.to_string())
+        .collect::<BTreeSet<_>>();
+        assert_eq!(value.all_keys(), expected);
+    }
+
+    #[test]
+    fn all_keys_of_scalars_and_arrays() {
+        assert!(Integer(1).all_keys().is_empty());
+        assert!(parse_json("[[], [1, 2]]").unwrap().all_keys().is_empty());
+    }
+
+    #[test]
+    fn node_count_and_depth_kitchen_sink() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        assert_eq!(value.node_count(), 14);
+        assert_eq!(value.depth(), 4);
+    }
+
+    #[test]
+    fn node_count_and_depth_small_values() {
+        assert_eq!((Null.node_count(), Null.depth()), (1, 0));
+        let empty = Array(vec![]);
+        assert_eq!((empty.node_count(), empty.depth()), (1, 1));
+        let nested = parse_json(r#"[1, {"a": [[]]}]"#).unwrap();
+        assert_eq!((nested.node_count(), nested.depth()), (5, 4));
+    }
+
+    #[test]
+    fn remove_from_simple_object() {
+        let mut value = parse_json(fixtures::SIMPLE_OBJECT).unwrap();
+        assert_eq!(
+            value.remove("message"),
+            Some(String("things are broken".into()))
+        );
+        assert_eq!(value.get("message"), None);
+        assert_eq!(value, Object(vec![("success".into(), False)]));
+        assert_eq!(value.remove("message"), None);
+    }
+
+    #[test]
+    fn remove_duplicate_key_removes_first() {
+        let mut value = parse_json(r#"{"a": 1, "a": 2}"#).unwrap();
+        assert_eq!(value.remove("a"), Some(Integer(1)));
+        assert_eq!(value.get("a"), Some(&Integer(2)));
+    }
+
+    #[test]
+    fn remove_on_non_object() {
+        assert_eq!(Array(vec![True]).remove("0"), None);
+    }
+
+    #[test]
+    fn default_and_empty_constructors() {
+        assert_eq!(JSONValue::default(), Null);
+        assert_eq!(JSONValue::empty_object(), Object(vec![]));
+        assert_eq!(JSONValue::empty_array(), Array(vec![]));
+        assert_eq!(JSONValue::empty_object().to_string(), "{}");
+        assert_eq!(JSONValue::empty_array().to_string(), "[]");
+    }
+
+    #[test]
+    fn take_leaves_null() {
+        let mut value = parse_json(fixtures::NESTED_OBJECT).unwrap();
+        let data = value.pointer("/data").cloned();
+        let taken = match &mut value {
+            Object(members) => members[0].1.take(),
+            _ => panic!("expected an object"),
+        };
+        assert_eq!(Some(taken), data);
+        assert_eq!(value, Object(vec![("data".into(), Null)]));
+    }
+
+    fn hash_of(value: &JSONValue) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        return hasher.finish();
+    }
+
+    #[test]
+    fn hash_set_dedups_equal_documents() {
+        let documents = [
+            fixtures::KITCHEN_SINK,
+            r#"{"a": [1, 2.5, "x"], "b": null}"#,
+            r#"{ "a" : [ 1 , 2.5 , "x" ] , "b" : null }"#,
+            r#"{"b": null, "a": [1, 2.5, "x"]}"#,
+        ];
+        let mut set = std::collections::HashSet::new();
+        for document in documents.iter().chain(&documents) {
+            set.insert(parse_json(document).unwrap());
+        }
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&parse_json(fixtures::KITCHEN_SINK).unwrap()));
+
+        for value in all_variants() {
+            assert_eq!(hash_of(&value), hash_of(&value.clone()));
+        }
+    }
+
+    #[test]
+    fn hash_agrees_with_semantic_eq() {
+        let value = parse_json(r#"{"a": 1, "b": [true, {"c": 2, "d": 3}]}"#).unwrap();
+        let reordered = parse_json(r#"{"b": [true, {"d": 3, "c": 2.0}], "a": 1.0}"#).unwrap();
+        assert!(value.semantic_eq(&reordered));
+        assert_eq!(hash_of(&value), hash_of(&reordered));
+
+        assert_eq!(hash_of(&Number(-0.0)), hash_of(&Number(0.0)));
+        assert_ne!(hash_of(&Number(1.5)), hash_of(&Number(2.5)));
+        assert_ne!(hash_of(&True), hash_of(&False));
+        assert_ne!(
+            hash_of(&Array(vec![Integer(1), Integer(2)])),
+            hash_of(&Array(vec![Integer(2), Integer(1)]))
+        );
+    }
+
+    #[test]
+    fn pointer_set_builds_from_scratch() {
+        let mut value = Object(vec![]);
+        value.pointer_set("/object/thing", Integer(1)).unwrap();
+        value
+            .pointer_set("/object/items/-/type", "item thingo".into())
+            .unwrap();
+        value.pointer_set("/object/items/-", True).unwrap();
+        value.pointer_set("/object/items/2", "hey!".into()).unwrap();
+        value.pointer_set("/object/items/3/0", False).unwrap();
+        value.pointer_set("/object/items/3/-", True).unwrap();
+        value.pointer_set("/object/a~1b~0c", Null).unwrap();
+        assert_eq!(
+            Ok(value),
+            parse_json(
+                r#"{"object": {"thing": 1, "items": [{"type": "item thingo"}, true, "hey!", [false, true]], "a/b~c": null}}"#
+            )
+        );
+    }
+
+    #[test]
+    fn pointer_set_replaces() {
+        let mut value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        value.pointer_set("/object/thing", Integer(2)).unwrap();
+        value.pointer_set("/object/items/1", Null).unwrap();
+        assert_eq!(value.pointer("/object/thing"), Some(&Integer(2)));
+        assert_eq!(value.pointer("/object/items/1"), Some(&Null));
+        assert_eq!(
+            value.pointer("/object/items").unwrap().elements().count(),
+            4
+        );
+
+        value.pointer_set("", True).unwrap();
+        assert_eq!(value, True);
+    }
+
+    #[test]
+    fn pointer_set_conflicts() {
+        let mut value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let before = value.clone();
+        let error = value.pointer_set("/object/thing/x", Null).unwrap_err();
+        assert_eq!(error.kind(), &ParseJSONErrorKind::PathConflict);
+        assert_eq!(error.position(), 14);
+
+        for path in ["/object/items/x", "/object/items/5", "/object/items/01"] {
+            assert_eq!(
+                value.pointer_set(path, Null).unwrap_err().kind(),
+                &ParseJSONErrorKind::PathConflict
+            );
+        }
+        assert_eq!(
+            value.pointer_set("object", Null).unwrap_err().kind(),
+            &ParseJSONErrorKind::InvalidPointer
+        );
+        assert_eq!(value, before);
+
+        // Conflicts inside containers that would have been made
+        for (path, position) in [
+            ("/a/b/5", 5),
+            ("/object/new/-/3", 14),
+            ("/object/items/4/1", 16),
+        ] {
+            let error = value.pointer_set(path, Null).unwrap_err();
+            assert_eq!(error.kind(), &ParseJSONErrorKind::PathConflict, "{}", path);
+            assert_eq!(error.position(), position, "{}", path);
+            assert_eq!(value, before, "{}", path);
+        }
+    }
+
+    #[test]
+    fn redact_nested_keys() {
+        let mut value = parse_json(fixtures::WITH_SECRETS).unwrap();
+        value.redact(&["password", "token"]);
+        assert_eq!(value.pointer("/user/password"), Some(&String("***".into())));
+        assert_eq!(
+            value.pointer("/user/sessions/0/token"),
+            Some(&String("***".into()))
+        );
+        assert_eq!(
+            value.pointer("/user/sessions/0/Password/hash"),
+            Some(&String("abc".into()))
+        );
+        assert_eq!(
+            value.pointer("/user/sessions/1"),
+            Some(&String("password".into()))
+        );
+        assert_eq!(value.pointer("/user/name"), Some(&String("soorria".into())));
+    }
+
+    #[test]
+    fn redact_ignoring_case() {
+        let mut value = parse_json(fixtures::WITH_SECRETS).unwrap();
+        value.redact_with(&["PASSWORD"], true);
+        assert_eq!(value.pointer("/user/password"), Some(&String("***".into())));
+        assert_eq!(
+            value.pointer("/user/sessions/0/Password"),
+            Some(&String("***".into()))
+        );
+
+        let mut kitchen_sink = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let before = kitchen_sink.clone();
+        kitchen_sink.redact_with(&["password"], true);
+        assert_eq!(kitchen_sink, before);
+    }
+
+    #[test]
+    fn raw_number_accessors_and_equality() {
+        let raw = |text: &str| RawNumber(text.to_string());
+        assert_eq!(raw("12").as_i64(), Some(12));
+        assert_eq!(raw("12.0").as_i64(), None);
+        assert_eq!(raw("2.5e1").as_f64(), Some(25.0));
+        assert_eq!(raw("1").value_type(), ValueType::Number);
+
+        assert!(raw("12").semantic_eq(&Integer(12)));
+        assert!(raw("1.50").semantic_eq(&Number(1.5)));
+        assert!(Number(1.0).semantic_eq(&raw("1")));
+        assert!(raw("100").semantic_eq(&raw("1e2")));
+        assert!(!raw("1").semantic_eq(&raw("2")));
+        assert_eq!(hash_of(&raw("1e2")), hash_of(&Integer(100)));
+        assert_eq!(raw("1.50").to_canonical_string(), "1.5");
+    }
+
+    #[test]
+    fn get_ci_ignores_ascii_case() {
+        let value = parse_json(fixtures::SIMPLE_OBJECT).unwrap();
+        let message = Some(&String("things are broken".into()));
+        assert_eq!(value.get_ci("Message"), message);
+        assert_eq!(value.get_ci("MESSAGE"), message);
+        assert_eq!(value.get_ci("message"), message);
+        assert_eq!(value.get("Message"), None);
+        assert_eq!(value.get_ci("messages"), None);
+        assert_eq!(Array(vec![]).get_ci("message"), None);
+
+        let ambiguous = parse_json(r#"{"ID": 1, "id": 2}"#).unwrap();
+        assert_eq!(ambiguous.get_ci("id"), Some(&Integer(1)));
+    }
+
+    #[test]
+    fn sort_keys_recursively() {
+        let mut value = parse_json(
+            r#"{"c": {"z": 1, "a": 2, "m": [{"y": 1, "b": 2}]}, "a": 1, "B": 2, "a": 3}"#,
+        )
+        .unwrap();
+        let before = value.clone();
+        value.sort_keys();
+        assert!(value.semantic_eq(&before));
+        assert_eq!(
+            value.to_string(),
+            r#"{"B":2,"a":1,"a":3,"c":{"a":2,"m":[{"b":2,"y":1}],"z":1}}"#
+        );
+    }
+
+    #[test]
+    fn into_accessors() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let mut object = value
+            .into_object()
+            .unwrap()
+            .remove(0)
+            .1
+            .into_object()
+            .unwrap();
+        let keys: Vec<_> = object.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["thing", "another", "true", "exists", "items"]);
+
+        let items = object.pop().unwrap().1.into_array().unwrap();
+        assert_eq!(items[2].clone().into_string(), Some("hey!".to_string()));
+        assert_eq!(object.remove(1).1.into_f64(), Some(2.0e10));
+        assert_eq!(object.remove(0).1.into_f64(), Some(1.0));
+
+        let mut interned = parse_json(fixtures::SIMPLE_OBJECT).unwrap();
+        interned.intern_keys();
+        assert_eq!(interned.into_object().unwrap()[0].0, "message");
+    }
+
+    #[test]
+    fn into_accessors_type_mismatch() {
+        for value in all_variants() {
+            let value_type = value.value_type();
+            assert_eq!(
+                value.clone().into_object().is_some(),
+                value_type == ValueType::Object
+            );
+            assert_eq!(
+                value.clone().into_array().is_some(),
+                value_type == ValueType::Array
+            );
+            assert_eq!(
+                value.clone().into_string().is_some(),
+                value_type == ValueType::String
+            );
+            assert_eq!(value.into_f64().is_some(), value_type == ValueType::Number);
+        }
+    }
+
+    #[test]
+    fn into_iter_owned_array() {
+        let value = parse_json(r#"[1, "two", [3]]"#).unwrap();
+        let mut items = vec![];
+        for item in value {
+            items.push(item);
+        }
+        assert_eq!(
+            items,
+            vec![Integer(1), "two".into(), Array(vec![Integer(3)])]
+        );
+
+        for value in all_variants() {
+            let is_array = value.value_type() == ValueType::Array;
+            assert_eq!(value.into_iter().next().is_some(), is_array);
+        }
+    }
+
+    #[test]
+    fn into_entries_owned_object() {
+        let value = parse_json(fixtures::SIMPLE_OBJECT).unwrap();
+        let entries: Vec<(std::string::String, JSONValue)> = value.into_entries().collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("message".to_string(), "things are broken".into()),
+                ("success".to_string(), False),
+            ]
+        );
+        assert_eq!(Array(vec![True]).into_entries().count(), 0);
+    }
+
+    #[test]
+    fn normalize_numbers_whole_values() {
+        let mut value = Number(5.0);
+        value.normalize_numbers();
+        assert_eq!(value, Integer(5));
+        assert_eq!(value.to_string(), "5");
+
+        let mut value = Number(5.5);
+        value.normalize_numbers();
+        assert_eq!(value, Number(5.5));
+
+        for number in [-0.0, 1e19, -1e300, f64::NAN, f64::INFINITY] {
+            let mut value = Number(number);
+            value.normalize_numbers();
+            assert!(matches!(value, Number(_)), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn normalize_numbers_nested() {
+        let mut value =
+            parse_json(r#"{"a": [1.0, 2.5, {"b": -3e2}], "c": "4.0", "d": 7}"#).unwrap();
+        value.normalize_numbers();
+        assert_eq!(
+            value,
+            parse_json(r#"{"a": [1, 2.5, {"b": -300}], "c": "4.0", "d": 7}"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn find_all_strings_in_kitchen_sink() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        assert_eq!(
+            value.find_all(|value| matches!(value, String(_))),
+            vec![&String("item thingo".into()), &String("hey!".into())]
+        );
+    }
+
+    #[test]
+    fn find_all_containers_and_nothing() {
+        let value = parse_json(r#"[{"a": [true]}, [], 1]"#).unwrap();
+        let arrays = value.find_all(|value| matches!(value, Array(_)));
+        assert_eq!(arrays.len(), 3);
+        assert!(std::ptr::eq(arrays[0], &value));
+        assert_eq!(arrays[1], &Array(vec![True]));
+        assert_eq!(value.find_all(JSONValue::is_null), Vec::<&JSONValue>::new());
+    }
+
+    #[test]
+    fn replace_all_trims_strings() {
+        let mut value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        value.pointer_set("/object/padded", " x \t".into()).unwrap();
+        value.replace_all(|value| match value {
+            String(string) => Some(string.trim().into()),
+            _ => None,
+        });
+        assert_eq!(value.pointer("/object/padded"), Some(&String("x".into())));
+        assert_eq!(
+            value.pointer("/object/items/0/type"),
+            Some(&String("item thingo".into()))
+        );
+        assert!(value
+            .find_all(|value| matches!(value, String(string) if string.trim() != string.as_str()))
+            .is_empty());
+    }
+
+    #[test]
+    fn replace_all_skips_replacements() {
+        let mut value = parse_json(r#"[[1], {"a": [2]}, 3.5]"#).unwrap();
+        value.replace_all(|value| match value {
+            Array(items) if items.len() == 1 => Some(Array(vec![value.clone(), Null])),
+            Number(number) => Some(Number(number.round())),
+            _ => None,
+        });
+        assert_eq!(
+            value,
+            parse_json(r#"[[[1], null], {"a": [[2], null]}, 4.0]"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn as_map_simple_object() {
+        let value = parse_json(fixtures::SIMPLE_OBJECT).unwrap();
+        let map = value.as_map().unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["message"], &String("things are broken".into()));
+        assert_eq!(map["success"], &False);
+        assert_eq!(
+            map.keys().copied().collect::<Vec<_>>(),
+            vec!["message", "success"]
+        );
+    }
+
+    #[test]
+    fn as_map_duplicates_and_non_objects() {
+        let value = parse_json(r#"{"b": 1, "a": 2, "b": 3}"#).unwrap();
+        let map = value.as_map().unwrap();
+        assert_eq!(
+            map.into_iter().collect::<Vec<_>>(),
+            vec![("a", &Integer(2)), ("b", &Integer(1))]
+        );
+        assert_eq!(parse_json("[1]").unwrap().as_map(), None);
+    }
+
+    #[test]
+    fn truncate_depth_kitchen_sink() {
+        let value = parse_json(fixtures::KITCHEN_SINK).unwrap();
+        let truncated = value.truncate_depth(2);
+        assert_eq!(truncated.depth(), 2);
+        assert_eq!(
+            truncated.pointer("/object/items"),
+            Some(&String("…".into()))
+        );
+        assert_eq!(truncated.pointer("/object/items/0/type"), None);
+        assert_eq!(
+            truncated.pointer("/object/thing"),
+            value.pointer("/object/thing")
+        );
+        assert_eq!(truncated.get("object").unwrap().entries().count(), 5);
+
+        assert_eq!(value.truncate_depth(value.depth()), value);
+        assert_eq!(value.truncate_depth(0), String("…".into()));
+    }
+
+    #[test]
+    fn truncate_depth_scalars_and_empty_containers() {
+        assert_eq!(Integer(1).truncate_depth(0), Integer(1));
+        let value = parse_json(r#"[[], [1, {}], null]"#).unwrap();
+        assert_eq!(
+            value.truncate_depth(1),
+            parse_json(r#"["…", "…", null]"#).unwrap()
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::JSONValue;
+
+/// Callbacks for a read-only walk over a [`JSONValue`] with
+/// [`JSONValue::visit`]. Every method does nothing by default, so only the
+/// ones needed have to be implemented
+pub trait Visitor {
+    fn visit_string(&mut self, _string: &str) {}
+
+    fn visit_number(&mut self, _number: f64) {}
+
+    /// Called for an `Integer`, which is passed on to `visit_number` unless
+    /// this is overridden
+    fn visit_integer(&mut self, integer: i64) {
+        self.visit_number(integer as f64);
+    }
+
+    /// Called with a `RawNumber`'s text, which is parsed and passed on to
+    /// `visit_number` unless this is overridden
+    fn visit_raw_number(&mut self, text: &str) {
+        self.visit_number(text.parse().unwrap_or(f64::NAN));
+    }
+
+    fn visit_bool(&mut self, _boolean: bool) {}
+
+    fn visit_null(&mut self) {}
+
+    /// Called before an object's members are visited
+    fn enter_object(&mut self) {}
+
+    /// Called with each member's key, before its value is visited
+    fn visit_key(&mut self, _key: &str) {}
+
+    /// Called after an object's members are visited
+    fn exit_object(&mut self) {}
+
+    /// Called before an array's items are visited
+    fn enter_array(&mut self) {}
+
+    /// Called after an array's items are visited
+    fn exit_array(&mut self) {}
+}
+
+impl JSONValue {
+    /// Walks the tree depth-first, calling `visitor` for each value in the
+    /// order they'd be written out
+    pub fn visit<V: Visitor>(&self, visitor: &mut V) {
+        match self {
+            JSONValue::String(string) => visitor.visit_string(string),
+            JSONValue::Number(number) => visitor.visit_number(*number),
+            JSONValue::Integer(integer) => visitor.visit_integer(*integer),
+            JSONValue::RawNumber(text) => visitor.visit_raw_number(text),
+            JSONValue::Object(members) => {
+                visitor.enter_object();
+                for (key, value) in members {
+                    visitor.visit_key(key);
+                    value.visit(visitor);
+                }
+                visitor.exit_object();
+            }
+            JSONValue::Array(items) => {
+                visitor.enter_array();
+                for item in items {
+                    item.visit(visitor);
+                }
+                visitor.exit_array();
+            }
+            JSONValue::True => visitor.visit_bool(true),
+            JSONValue::False => visitor.visit_bool(false),
+            JSONValue::Null => visitor.visit_null(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, parse_json};
+
+    #[derive(Default)]
+    struct StringCounter {
+        strings: usize,
+    }
+
+    impl Visitor for StringCounter {
+        fn visit_string(&mut self, _: &str) {
+            self.strings += 1;
+        }
+    }
+
+    #[test]
+    fn visit_counts_strings_in_kitchen_sink() {
+        let mut counter = StringCounter::default();
+        parse_json(fixtures::KITCHEN_SINK)
+            .unwrap()
+            .visit(&mut counter);
+        assert_eq!(counter.strings, 2);
+    }
+
+    /// Writes down every callback, to check the order they come in
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<std::string::String>,
+    }
+
+    impl Visitor for Recorder {
+        fn visit_string(&mut self, string: &str) {
+            self.events.push(format!("string {}", string));
+        }
+
+        fn visit_number(&mut self, number: f64) {
+            self.events.push(format!("number {}", number));
+        }
+
+        fn visit_key(&mut self, key: &str) {
+            self.events.push(format!("key {}", key));
+        }
+
+        fn enter_object(&mut self) {
+            self.events.push("{".to_string());
+        }
+
+        fn exit_object(&mut self) {
+            self.events.push("}".to_string());
+        }
+
+        fn enter_array(&mut self) {
+            self.events.push("[".to_string());
+        }
+
+        fn exit_array(&mut self) {
+            self.events.push("]".to_string());
+        }
+    }
+
+    #[test]
+    fn visit_order() {
+        let mut recorder = Recorder::default();
+        parse_json(r#"{"a": [1, 2.5], "b": "x", "c": null}"#)
+            .unwrap()
+            .visit(&mut recorder);
+        assert_eq!(
+            recorder.events,
+            vec![
+                "{",
+                "key a",
+                "[",
+                "number 1",
+                "number 2.5",
+                "]",
+                "key b",
+                "string x",
+                "key c",
+                "}"
+            ]
+        );
+    }
+}